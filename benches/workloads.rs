@@ -0,0 +1,126 @@
+//! Segmenting and measuring representative inputs, from plain ASCII and typical log output to pathological
+//! escape sequences.
+//!
+//! Run `cargo bench --bench workloads`, or pick one group: `cargo bench --bench workloads -- width`.
+//!
+//! For example, skipping the grapheme segmenter in runs of plain ASCII took
+//! `cargo bench --bench workloads -- '^segment/print_positions/log lines$'` (the plain log lines, 24 KB)
+//! from 1.01 ms to 197 µs, measured on one core of an Intel Xeon virtual machine with Rust 1.95.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use print_positions::coordinates::{position_at_column, Coordinates};
+use print_positions::width::WidthProfile;
+use print_positions::{
+    display_width, print_position_data, print_position_info, print_positions, Segmenter,
+};
+use std::hint::black_box;
+
+// about this many bytes of each input
+const SIZE: usize = 64 * 1024;
+
+fn repeat(piece: &str) -> String {
+    piece.repeat(SIZE / piece.len() + 1)
+}
+
+// typical log output: mostly ASCII, some of it colored with SGR escape sequences
+fn log_lines(colored: bool) -> String {
+    (0..200)
+        .map(|i| {
+            let level = if colored {
+                ["\u{1b}[32mINFO\u{1b}[0m", "\u{1b}[1;33mWARN\u{1b}[0m"][i % 2]
+            } else {
+                ["INFO", "WARN"][i % 2]
+            };
+            format!(
+                "2024-05-17T12:34:{:02}.{:03}Z {level} server::http: request completed method=GET path=/api/v1/items/{i} status=200 elapsed_ms={}\n",
+                i % 60,
+                i * 7 % 1000,
+                i % 97
+            )
+        })
+        .collect()
+}
+
+fn inputs() -> Vec<(&'static str, String)> {
+    vec![
+        ("ascii", repeat("The quick brown fox jumps over the lazy dog. ")),
+        ("log lines", log_lines(false)),
+        ("colored log lines", log_lines(true)),
+        (
+            "heavy sgr",
+            repeat("\u{1b}[1;31mE\u{1b}[0m\u{1b}[38;5;208mrr\u{1b}[0m \u{1b}[48;2;10;20;30mor\u{1b}[m "),
+        ),
+        ("cjk", repeat("\u{6f22}\u{5b57}\u{306f}\u{65e5}\u{672c}\u{8a9e}\u{3068}\u{4e2d}\u{6587}\u{3002}")),
+        (
+            "emoji zwj",
+            repeat("\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}\u{200d}\u{1f466} \u{1f3f3}\u{fe0f}\u{200d}\u{1f308}\u{1f44d}\u{1f3fd}"),
+        ),
+        (
+            "osc heavy",
+            repeat("\u{1b}]8;;https://example.com/docs\u{1b}\\link\u{1b}]8;;\u{1b}\\ \u{1b}]0;title\u{7}"),
+        ),
+        (
+            "unterminated osc",
+            format!("\u{1b}]0;{}", repeat("no terminator ")),
+        ),
+        (
+            "unterminated csi",
+            format!("\u{1b}[{}", repeat("1;")),
+        ),
+    ]
+}
+
+fn segment(c: &mut Criterion) {
+    let mut group = c.benchmark_group("segment");
+    for (name, text) in inputs() {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(
+            BenchmarkId::new("print_positions", name),
+            &text,
+            |b, text| b.iter(|| print_positions(black_box(text)).count()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("print_position_data", name),
+            &text,
+            |b, text| b.iter(|| print_position_data(black_box(text)).count()),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("print_position_info", name),
+            &text,
+            |b, text| b.iter(|| print_position_info(black_box(text)).count()),
+        );
+    }
+    group.finish();
+}
+
+fn width(c: &mut Criterion) {
+    let mut group = c.benchmark_group("width");
+    for (name, text) in inputs() {
+        group.throughput(Throughput::Bytes(text.len() as u64));
+        group.bench_with_input(BenchmarkId::new("display_width", name), &text, |b, text| {
+            b.iter(|| display_width(black_box(text)))
+        });
+        let legacy = Segmenter::new().profile(WidthProfile::XtermLegacy);
+        group.bench_with_input(BenchmarkId::new("xterm legacy", name), &text, |b, text| {
+            b.iter(|| legacy.display_width(black_box(text)))
+        });
+        let attach = Segmenter::new().attach_zero_width(true);
+        group.bench_with_input(
+            BenchmarkId::new("attach zero width", name),
+            &text,
+            |b, text| b.iter(|| attach.display_width(black_box(text))),
+        );
+        group.bench_with_input(BenchmarkId::new("coordinates", name), &text, |b, text| {
+            b.iter(|| Coordinates::new(black_box(text)).end())
+        });
+        group.bench_with_input(
+            BenchmarkId::new("position_at_column", name),
+            &text,
+            |b, text| b.iter(|| position_at_column(black_box(text), usize::MAX)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, segment, width);
+criterion_main!(benches);
@@ -2,6 +2,8 @@
 //! when used for padding or filling fixed width fields
 //! for display on a screen with monospace fonts and unicode + emoji support.
 
+#![allow(clippy::needless_lifetimes)]
+
 use anyhow::Result;
 use print_positions::print_position_data;
 
@@ -0,0 +1,85 @@
+/* print_positions C interface, build with cargo feature `ffi`. */
+
+#ifndef PRINT_POSITIONS_H
+#define PRINT_POSITIONS_H
+
+/* Generated by cbindgen from src/ffi.rs, don't edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+// Outcome of a call through the C interface.
+typedef enum PpStatus {
+  // Success, results have been written.
+  PP_STATUS_OK = 0,
+  // A pointer argument was NULL (other than a string or buffer of length 0).
+  PP_STATUS_NULL_POINTER = 1,
+  // The input string isn't valid UTF-8.
+  PP_STATUS_INVALID_UTF8 = 2,
+  // The output buffer is too small.  The length it needs has been written.
+  PP_STATUS_BUFFER_TOO_SMALL = 3,
+  // An offset argument is past the end of the input string.
+  PP_STATUS_OUT_OF_BOUNDS = 4,
+  // An internal error: the function panicked.
+  PP_STATUS_PANIC = 5,
+} PpStatus;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Count the print positions in `s[..len]`, storing the count in `*count`.
+//
+// # Safety
+// `s` must point to `len` readable bytes and `count` must be valid for writing a `size_t`.
+enum PpStatus pp_count(const uint8_t *s, size_t len, size_t *count);
+
+// Measure the number of columns `s[..len]` occupies on the screen, storing it in `*width`.
+//
+// # Safety
+// `s` must point to `len` readable bytes and `width` must be valid for writing a `size_t`.
+enum PpStatus pp_display_width(const uint8_t *s, size_t len, size_t *width);
+
+// Find the first print position boundary after byte `offset` of `s[..len]`, storing it in `*next`.
+// At the end of the string, that's `len`.
+//
+// # Safety
+// `s` must point to `len` readable bytes and `next` must be valid for writing a `size_t`.
+enum PpStatus pp_next_boundary(const uint8_t *s, size_t len, size_t offset, size_t *next);
+
+// Copy `s[..len]` without its escape sequences into `buf[..buf_len]`, storing the length of the copy in `*written`.
+//
+// If the copy doesn't fit, nothing is copied, `*written` is the length it needs and the result is
+// `PP_STATUS_BUFFER_TOO_SMALL`.  `buf` may be NULL if `buf_len` is 0, to ask for the length.
+//
+// # Safety
+// `s` must point to `len` readable bytes, `buf` to `buf_len` writable bytes (not overlapping `s`),
+// and `written` must be valid for writing a `size_t`.
+enum PpStatus pp_strip_escapes(const uint8_t *s,
+                               size_t len,
+                               uint8_t *buf,
+                               size_t buf_len,
+                               size_t *written);
+
+// Copy as many leading print positions of `s[..len]` as fit in `width` columns into `buf[..buf_len]`,
+// closing any style or hyperlink still open at the cut, like `truncate` in the crate's `text` module,
+// and store the length of the copy in `*written`.
+//
+// If the copy doesn't fit, nothing is copied, `*written` is the length it needs and the result is
+// `PP_STATUS_BUFFER_TOO_SMALL`.  `buf` may be NULL if `buf_len` is 0, to ask for the length.
+//
+// # Safety
+// `s` must point to `len` readable bytes, `buf` to `buf_len` writable bytes (not overlapping `s`),
+// and `written` must be valid for writing a `size_t`.
+enum PpStatus pp_truncate(const uint8_t *s,
+                          size_t len,
+                          size_t width,
+                          uint8_t *buf,
+                          size_t buf_len,
+                          size_t *written);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PRINT_POSITIONS_H */
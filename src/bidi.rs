@@ -0,0 +1,107 @@
+//! Visual order of print positions in bidirectional text (requires feature `bidi`).
+//!
+//! Print positions are stored in logical order, the order they're read in, but a line mixing left-to-right
+//! text with right-to-left text such as Hebrew or Arabic is displayed in a different, visual, order.
+//! [visual_order] applies the [Unicode Bidirectional Algorithm (UAX#9)](https://www.unicode.org/reports/tr9/)
+//! to a line and returns the permutation of its print positions a bidi-aware terminal would display.
+//! Escape sequences stay attached to their graphemes and are ignored by the algorithm, since their
+//! characters would otherwise be taken for left-to-right text.
+//! Note that the escape sequences are then out of order too: to display print positions in visual order
+//! with their styles, reopen the style in effect at each one (see [style](crate::style)).
+//!
+//! ```rust
+//! use print_positions::bidi::visual_order;
+//! use print_positions::print_position_data;
+//!
+//! let line = "abc \u{1b}[1m\u{5d0}\u{5d1}\u{5d2}\u{1b}[0m";   // Hebrew alef, bet, gimel in bold
+//! let positions: Vec<_> = print_position_data(line).collect();
+//! let order = visual_order(line);
+//! assert_eq!(order, vec![0, 1, 2, 3, 6, 5, 4]);
+//!
+//! let visual: String = order.iter().map(|&i| positions[i]).collect();
+//! assert_eq!(visual, "abc \u{5d2}\u{1b}[0m\u{5d1}\u{1b}[1m\u{5d0}");
+//! ```
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use unicode_bidi::{BidiInfo, Level};
+
+use crate::print_position_info;
+
+#[cfg(test)]
+mod tests;
+
+/// Base (paragraph) direction of a line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Direction of the first strong (left-to-right or right-to-left) character in the line,
+    /// left-to-right if there is none.
+    #[default]
+    Auto,
+    /// Left-to-right, as for English.
+    LeftToRight,
+    /// Right-to-left, as for Hebrew or Arabic.
+    RightToLeft,
+}
+
+impl Direction {
+    fn level(self) -> Option<Level> {
+        match self {
+            Direction::Auto => None,
+            Direction::LeftToRight => Some(Level::ltr()),
+            Direction::RightToLeft => Some(Level::rtl()),
+        }
+    }
+}
+
+/// Indices of the print positions of `line` (as returned by [print_positions](crate::print_positions))
+/// in visual order, left to right, with the base direction taken from the line itself.
+///
+/// See [visual_order_with] to choose the base direction.
+pub fn visual_order(line: &str) -> Vec<usize> {
+    visual_order_with(line, Direction::Auto)
+}
+
+/// Indices of the print positions of `line` in visual order, left to right, for base direction `direction`.
+///
+/// `line` should be a single line: the algorithm starts a new paragraph at each paragraph separator
+/// (such as `\n`), and each paragraph is reordered separately.
+///
+/// ```rust
+/// use print_positions::bidi::{visual_order_with, Direction};
+///
+/// let line = "\u{5d0} abc";
+/// assert_eq!(visual_order_with(line, Direction::Auto), vec![2, 3, 4, 1, 0]);
+/// assert_eq!(visual_order_with(line, Direction::LeftToRight), vec![0, 1, 2, 3, 4]);
+/// ```
+pub fn visual_order_with(line: &str, direction: Direction) -> Vec<usize> {
+    // the graphemes without their escape sequences, and where each print position's grapheme starts
+    let mut text = String::new();
+    let mut starts = vec![];
+    for p in print_position_info(line) {
+        starts.push(text.len());
+        text.push_str(p.grapheme());
+    }
+
+    let info = BidiInfo::new(&text, direction.level());
+    let mut order = Vec::with_capacity(starts.len());
+    let mut first = 0;
+    for para in &info.paragraphs {
+        let levels = info.reordered_levels(para, para.range.clone());
+        let count = starts[first..].partition_point(|&start| start < para.range.end);
+        let position_levels: Vec<_> = starts[first..first + count]
+            .iter()
+            .map(|&start| levels[start])
+            .collect();
+        order.extend(
+            BidiInfo::reorder_visual(&position_levels)
+                .into_iter()
+                .map(|i| first + i),
+        );
+        first += count;
+    }
+    // print positions with no grapheme to reorder
+    order.extend(first..starts.len());
+    order
+}
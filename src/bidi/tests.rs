@@ -0,0 +1,66 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::print_position_data;
+
+fn visual(line: &str, direction: Direction) -> String {
+    let positions: Vec<_> = print_position_data(line).collect();
+    visual_order_with(line, direction)
+        .into_iter()
+        .map(|i| positions[i])
+        .collect()
+}
+
+#[test]
+fn reordering() -> Result<()> {
+    use Direction::*;
+    let cases = [
+        ("", Auto, ""),
+        ("abc", Auto, "abc"),
+        ("abc", RightToLeft, "abc"),
+        ("\u{5d0}\u{5d1}", Auto, "\u{5d1}\u{5d0}"),
+        ("\u{5d0}\u{5d1}", LeftToRight, "\u{5d1}\u{5d0}"),
+        // numbers stay left to right within right to left text
+        ("\u{5d0} 12 \u{5d1}", Auto, "\u{5d1} 12 \u{5d0}"),
+        // Arabic, with a combining mark kept in its grapheme
+        ("x \u{627}\u{64e}\u{644}", Auto, "x \u{644}\u{627}\u{64e}"),
+        ("a\u{5d0}", RightToLeft, "\u{5d0}a"),
+    ];
+    for (line, direction, expected) in cases {
+        assert_eq!(visual(line, direction), expected, "{line:?} {direction:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn escapes_are_ignored() -> Result<()> {
+    // the 'm' of the escape would be left-to-right text, splitting the Hebrew in two
+    let line = "\u{5d0}\u{1b}[31m\u{5d1}\u{1b}[0m\u{5d2}";
+    assert_eq!(visual_order(line), vec![2, 1, 0]);
+    assert_eq!(
+        visual(line, Direction::Auto),
+        "\u{5d2}\u{1b}[31m\u{5d1}\u{1b}[0m\u{5d0}"
+    );
+
+    assert_eq!(visual_order("\u{1b}[1m"), vec![0]);
+    Ok(())
+}
+
+#[test]
+fn paragraphs() -> Result<()> {
+    // each paragraph is reordered on its own
+    let line = "\u{5d0}\u{5d1}\nab\u{5d2}\u{5d3}";
+    let order = visual_order(line);
+    assert_eq!(order, vec![2, 1, 0, 3, 4, 6, 5]);
+
+    let mut sorted = order.clone();
+    sorted.sort_unstable();
+    assert_eq!(
+        sorted,
+        (0..print_position_data(line).count()).collect::<Vec<_>>()
+    );
+    Ok(())
+}
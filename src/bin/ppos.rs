@@ -0,0 +1,174 @@
+//! `ppos`: measure and manipulate text containing ANSI escape sequences and Unicode graphemes,
+//! counting print positions rather than bytes or chars.
+//!
+//! Each subcommand reads lines from stdin and writes the results to stdout.
+//! Build with `cargo install print-positions --features cli`.
+
+use std::io::{self, BufRead, BufWriter, Write};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use print_positions::display_width;
+use print_positions::lines;
+use print_positions::table;
+use print_positions::text::{self, Align};
+
+/// Measure and manipulate terminal text by print positions.
+#[derive(Parser)]
+#[command(name = "ppos", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the display width of each line, in columns.
+    Width,
+    /// Remove escape sequences from each line.
+    Strip,
+    /// Cut each line down to at most WIDTH columns.
+    Truncate {
+        #[arg(long)]
+        width: usize,
+    },
+    /// Pad each line with spaces to WIDTH columns.
+    Pad {
+        #[arg(long)]
+        width: usize,
+        #[arg(long, value_enum, default_value_t = AlignArg::Left)]
+        align: AlignArg,
+    },
+    /// Wrap each line to at most WIDTH columns.
+    Wrap {
+        #[arg(long)]
+        width: usize,
+    },
+    /// Print print position N (counting from 0) of each line, or an empty line if it's shorter.
+    Index { n: usize },
+    /// Align the fields of the lines in columns, like `column -t`.
+    Column {
+        /// Field delimiter, fields are separated by whitespace if not specified.
+        #[arg(short = 's', long)]
+        delimiter: Option<String>,
+        /// Separator between columns in the output.
+        #[arg(short = 'o', long, default_value = "  ")]
+        separator: String,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum AlignArg {
+    Left,
+    Right,
+    Center,
+}
+
+impl From<AlignArg> for Align {
+    fn from(align: AlignArg) -> Self {
+        match align {
+            AlignArg::Left => Align::Left,
+            AlignArg::Right => Align::Right,
+            AlignArg::Center => Align::Center,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(
+        &cli.command,
+        io::stdin().lock(),
+        &mut BufWriter::new(io::stdout().lock()),
+    ) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("ppos: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(command: &Command, mut input: impl BufRead, out: &mut impl Write) -> io::Result<()> {
+    match command {
+        Command::Column {
+            delimiter,
+            separator,
+        } => {
+            // needs all the lines to determine column widths
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            out.write_all(table::columnize(&text, delimiter.as_deref(), separator).as_bytes())?;
+        }
+        Command::Truncate { .. } | Command::Wrap { .. } | Command::Index { .. } => {
+            // each line closes its style and hyperlink, so the next one needs them reopened
+            let mut text = String::new();
+            input.read_to_string(&mut text)?;
+            for line in carried_lines(&text) {
+                write_line(command, &line, out)?;
+            }
+        }
+        _ => {
+            for line in input.lines() {
+                write_line(command, &line?, out)?;
+            }
+        }
+    }
+    out.flush()
+}
+
+// The lines of `text`, split like `str::lines`, each starting with the escape sequences which reopen
+// the style and hyperlink carried over from the lines before it.
+fn carried_lines(text: &str) -> Vec<String> {
+    let mut carried = vec![];
+    // start of the line and its reopening escapes, while it's split up by other line terminators
+    let mut start = None;
+    for line in lines::lines(text) {
+        let (begin, mut content) = start.take().unwrap_or_else(|| {
+            let mut reopen = String::new();
+            if !line.style().is_default() {
+                reopen.push_str(&line.style().sgr().to_string());
+            }
+            if let Some(link) = line.hyperlink() {
+                reopen.push_str(&link.open().to_string());
+            }
+            (line.range().start, reopen)
+        });
+        if !matches!(line.terminator(), "\n" | "\r\n" | "") {
+            start = Some((begin, content));
+            continue;
+        }
+        let line = &text[begin..line.range().end];
+        if line.is_empty() {
+            content.clear();
+        }
+        content.push_str(line);
+        carried.push(content);
+    }
+    carried
+}
+
+fn write_line(command: &Command, line: &str, out: &mut impl Write) -> io::Result<()> {
+    match command {
+        Command::Width => writeln!(out, "{}", display_width(line)),
+        Command::Strip => writeln!(out, "{}", text::strip_escapes(line)),
+        Command::Truncate { width } => writeln!(out, "{}", text::truncate(line, *width)),
+        Command::Pad { width, align } => {
+            writeln!(out, "{}", text::pad(line, *width, (*align).into()))
+        }
+        Command::Wrap { width } => {
+            let wrapped = text::wrap(line, *width);
+            // an empty line wraps to no lines at all, but stays in the output
+            if wrapped.is_empty() {
+                writeln!(out)?;
+            }
+            for wrapped in wrapped {
+                writeln!(out, "{wrapped}")?;
+            }
+            Ok(())
+        }
+        Command::Index { n } => writeln!(out, "{}", text::slice(line, *n..n.saturating_add(1))),
+        Command::Column { .. } => unreachable!("handled by run"),
+    }
+}
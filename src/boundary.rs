@@ -0,0 +1,160 @@
+//! Print position boundaries around an arbitrary byte offset, found without segmenting the whole string.
+//!
+//! Segmentation can't simply start at the offset: escape sequences and grapheme clusters reach back before it.
+//! But the state of segmentation is known at some places, as long as no OSC sequence (the only escape sequence
+//! which can contain anything at all) is still open there: after two ASCII letters followed by another ASCII
+//! character (the second letter is a grapheme, even if the first ended an escape sequence), and close after
+//! a line feed.  So the functions here look back from the offset for the nearest such place, then segment from there.
+//!
+//! The cost of a lookup is local: it looks back at most `LOOKBACK` bytes for a place to start, then segments
+//! the few print positions up to the offset.  Whether an OSC sequence is open there is also looked for within
+//! `LOOKBACK` bytes, and only if neither its start nor its end is found does it scan the bytes further back.
+//! If there's no place to start within `LOOKBACK` bytes (say, a long line with no two ASCII letters together),
+//! or an OSC sequence is open there, it falls back to segmenting from the start of the string, which takes time
+//! proportional to the offset.
+
+use core::ops::Range;
+
+use crate::print_positions;
+
+#[cfg(test)]
+mod tests;
+
+/// Offset of the first print position boundary after `offset` in `s`, or `None` if `offset` is at the end of `s`.
+///
+/// Same as the first end offset greater than `offset` returned by [print_positions](crate::print_positions).
+/// Panics if `offset` is greater than the length of `s`.
+///
+/// The lookup is local: it looks back at most 4 KiB from `offset` for a place where segmentation can start
+/// (after two ASCII letters, or a line feed) and 4 KiB more for an OSC sequence still open there, then segments
+/// forward from it.  Only if there's no such place (say, in a long line of CJK text) or an OSC sequence is open
+/// does it segment from the start of `s`, taking time proportional to `offset`.
+///
+/// ```rust
+/// use print_positions::{next_boundary, prev_boundary, is_boundary};
+///
+/// let content = "ab\u{1b}[31me\u{301}\u{1b}[0m\ncd";
+/// assert_eq!(next_boundary(content, 1), Some(2));
+/// assert_eq!(next_boundary(content, 2), Some(14));    // escapes, grapheme and reset
+/// assert_eq!(next_boundary(content, 4), Some(14));    // from inside an escape sequence
+/// assert_eq!(prev_boundary(content, 14), Some(2));
+/// assert_eq!(prev_boundary(content, 15), Some(14));
+/// assert!(is_boundary(content, 15));
+/// assert!(!is_boundary(content, 8));
+/// assert_eq!(next_boundary(content, content.len()), None);
+/// ```
+pub fn next_boundary(s: &str, offset: usize) -> Option<usize> {
+    check(s, offset);
+    if offset == s.len() {
+        return None;
+    }
+    let start = resync_point(s, offset);
+    print_positions(&s[start..])
+        .map(|(_, end)| start + end)
+        .find(|&end| end > offset)
+}
+
+/// Offset of the last print position boundary before `offset` in `s`, or `None` if `offset` is 0.
+///
+/// Same as the last end offset less than `offset` returned by [print_positions](crate::print_positions),
+/// or 0 if there is none.
+/// Panics if `offset` is greater than the length of `s`.  Costs the same as [next_boundary].
+pub fn prev_boundary(s: &str, offset: usize) -> Option<usize> {
+    check(s, offset);
+    if offset == 0 {
+        return None;
+    }
+    let start = resync_point(s, offset);
+    let boundary = print_positions(&s[start..])
+        .map(|(_, end)| start + end)
+        .take_while(|&end| end < offset)
+        .last();
+    Some(boundary.unwrap_or(start))
+}
+
+/// Whether `offset` is a print position boundary in `s`: the start or end of `s`,
+/// or the end of one of the print positions returned by [print_positions](crate::print_positions).
+///
+/// Panics if `offset` is greater than the length of `s`.  Costs the same as [next_boundary].
+pub fn is_boundary(s: &str, offset: usize) -> bool {
+    check(s, offset);
+    if offset == 0 || offset == s.len() {
+        return true;
+    }
+    let start = resync_point(s, offset);
+    print_positions(&s[start..])
+        .map(|(_, end)| start + end)
+        .take_while(|&end| end <= offset)
+        .any(|end| end == offset)
+}
+
+fn check(s: &str, offset: usize) {
+    assert!(
+        offset <= s.len(),
+        "offset {offset} out of bounds for length {}",
+        s.len()
+    );
+}
+
+/// How far the boundary functions look back from an offset.  See the module documentation.
+pub(crate) const LOOKBACK: usize = 4096;
+
+/// A print position boundary before `offset` (or 0), from which segmenting the rest of `s` on its own
+/// finds the same print positions as segmenting all of `s`.
+pub(crate) fn resync_point(s: &str, offset: usize) -> usize {
+    let bytes = s.as_bytes();
+    for i in (offset.saturating_sub(LOOKBACK)..offset).rev() {
+        if after_two_letters(bytes, i) {
+            // The first letter is a grapheme or ends an escape sequence, either way the second is a grapheme,
+            // and nothing attaches to it.
+            return if osc_may_be_open(bytes, i) { 0 } else { i };
+        }
+        if bytes[i] == b'\n' {
+            let after = i + 1;
+            if osc_may_be_open(bytes, after) {
+                return 0;
+            }
+            // Segmentation is in the normal state after the line feed.  But the line feed might have ended an escape
+            // sequence rather than being a grapheme, and so might or might not have ended a print position:
+            // it's only certain that the first print position found from here ends where it would have anyway.
+            if let Some((_, end)) = print_positions(&s[after..]).next() {
+                if after + end < offset {
+                    return after + end;
+                }
+            }
+        }
+    }
+    0
+}
+
+/// Whether byte `i` of `bytes` is an ASCII character other than ESC, after two ASCII letters.
+fn after_two_letters(bytes: &[u8], i: usize) -> bool {
+    i >= 2
+        && bytes[i - 2].is_ascii_alphabetic()
+        && bytes[i - 1].is_ascii_alphabetic()
+        && bytes[i].is_ascii()
+        && bytes[i] != 0x1b
+}
+
+/// Whether an OSC sequence might still be open at offset `at` of `bytes`: an `ESC ]` comes after the last terminator.
+///
+/// Looks within `LOOKBACK` bytes first, and scans the bytes before that only if it finds neither.
+fn osc_may_be_open(bytes: &[u8], at: usize) -> bool {
+    let window = at.saturating_sub(LOOKBACK);
+    last_osc_event(bytes, window..at)
+        .or_else(|| last_osc_event(bytes, 0..window))
+        .unwrap_or(false)
+}
+
+/// The last byte in `range` of `bytes` which starts or ends an OSC sequence:
+/// `Some(true)` for the `]` of `ESC ]`, `Some(false)` for a terminator, `None` if there's neither.
+pub(crate) fn last_osc_event(bytes: &[u8], range: Range<usize>) -> Option<bool> {
+    let escaped = |i: usize| i > 0 && bytes[i - 1] == 0x1b;
+    range.rev().find_map(|i| match bytes[i] {
+        // after ESC, BEL continues the OSC
+        0x07 if !escaped(i) => Some(false),
+        b'\\' if escaped(i) => Some(false),
+        b']' if escaped(i) => Some(true),
+        _ => None,
+    })
+}
@@ -0,0 +1,122 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::fixtures::Random;
+
+// compare every offset with the boundaries found by iterating from the start
+fn check(s: &str) {
+    let mut boundaries = vec![0];
+    boundaries.extend(print_positions(s).map(|(_, end)| end));
+    for offset in 0..=s.len() {
+        let next = boundaries.iter().copied().find(|&b| b > offset);
+        let prev = boundaries.iter().copied().rev().find(|&b| b < offset);
+        assert_eq!(next_boundary(s, offset), next, "next {offset} in {s:?}");
+        assert_eq!(prev_boundary(s, offset), prev, "prev {offset} in {s:?}");
+        assert_eq!(
+            is_boundary(s, offset),
+            boundaries.contains(&offset),
+            "is {offset} in {s:?}"
+        );
+    }
+}
+
+#[test]
+fn same_as_iteration() -> Result<()> {
+    for s in [
+        "",
+        "a",
+        "ab\ncd\r\nef\n",
+        "\n\n\u{1b}[0m\n",
+        "a\n\u{1b}[0mb\n\u{1b}[0m",
+        // line feeds which end escape sequences rather than lines
+        "a\u{1b}[\nb\nc",
+        "a\u{1b}\nb\nc",
+        "\u{1b}\u{1b}\nb\nc",
+        // line feeds inside OSC sequences, terminated or not
+        "a\u{1b}]0;x\ny\u{7}b\nc\nd",
+        "a\u{1b}]0;x\ny\u{1b}\u{7}b\nc\nd",
+        "a\u{1b}]0;x\ny\nz",
+        "\u{1f1fa}\u{1f1fa}\n\u{1f1fa}\u{1f1fa}\u{1f1fa}",
+    ] {
+        check(s);
+    }
+    Ok(())
+}
+
+#[test]
+fn random_strings() -> Result<()> {
+    let mut random = Random(0x5eed);
+    for _ in 0..500 {
+        let pieces = random.below(20);
+        check(&random.text(pieces));
+    }
+    Ok(())
+}
+
+#[test]
+fn resyncs_nearby() -> Result<()> {
+    let s = "line one\nline two\n\u{1b}[0mline three";
+    assert_eq!(resync_point(s, 25), 24); // after "li"
+    assert_eq!(resync_point(s, 23), 17); // not after "ml", the reset doesn't end with a grapheme
+    assert_eq!(resync_point(s, 5), 4);
+    assert_eq!(resync_point(s, 2), 0);
+
+    // no letters, after the first print position of the line
+    let s = "\u{4e2d}\u{4e2d}\n\u{1b}[1m\u{4e2d}\u{4e2d}";
+    assert_eq!(resync_point(s, s.len()), 14);
+
+    // an OSC sequence might still be open
+    let s = "\u{1b}]0;title\nline two";
+    assert_eq!(resync_point(s, 15), 0);
+    assert_eq!(resync_point(s, 18), 0);
+    Ok(())
+}
+
+#[test]
+fn looks_back_a_bounded_distance() -> Result<()> {
+    // nowhere to start within LOOKBACK: segment from the start
+    let s = "\u{4e2d}".repeat(LOOKBACK);
+    assert_eq!(resync_point(&s, s.len()), 0);
+
+    // an OSC sequence open for less than LOOKBACK is found
+    let s = format!("\x1b]0;{}x", "ab".repeat(LOOKBACK / 4));
+    assert_eq!(resync_point(&s, s.len()), 0);
+    assert_eq!(prev_boundary(&s, s.len()), Some(0));
+
+    // and so is one open for longer
+    let s = format!("\x1b]0;{}x", "ab".repeat(LOOKBACK));
+    assert_eq!(resync_point(&s, s.len()), 0);
+
+    // long lines of ordinary text resync close to the offset
+    let s = "\u{1b}[1mlorem ipsum\u{1b}[0m ".repeat(10_000);
+    assert!(s.len() - resync_point(&s, s.len()) < 20);
+    check(&s[..LOOKBACK * 2]);
+    Ok(())
+}
+
+#[test]
+fn osc_longer_than_lookback() -> Result<()> {
+    // all one print position, like the iterator finds it
+    let s = format!("\x1b]0;{}", "ab c ".repeat(2000));
+    assert_eq!(print_positions(&s).collect::<Vec<_>>(), [(0, s.len())]);
+    for offset in [1, LOOKBACK, 9000, 9001, s.len() - 1] {
+        assert_eq!(next_boundary(&s, offset), Some(s.len()), "next {offset}");
+        assert_eq!(prev_boundary(&s, offset), Some(0), "prev {offset}");
+        assert!(!is_boundary(&s, offset), "is {offset}");
+    }
+
+    // terminated long ago, the lookup stays local
+    let s = format!("\x1b]0;title\x07{}", "ab c ".repeat(2000));
+    assert!(resync_point(&s, 9001) > 9001 - LOOKBACK);
+    check(&format!("\x1b]0;{}\x07ab c", "ab c ".repeat(LOOKBACK / 4)));
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn out_of_bounds() {
+    next_boundary("abc", 4);
+}
@@ -0,0 +1,231 @@
+//! Conversion between the coordinate systems used to address a position in text.
+//!
+//! Rust indexes strings by UTF-8 byte offset, editors count `char`s, LSP and JavaScript count UTF-16 code units,
+//! and a terminal UI counts print positions or display columns.  [Coordinates] indexes a string once,
+//! then converts an offset in any of these into all the others.
+//!
+//! Every conversion resolves to a print position boundary, described by a [Coordinate].
+//! An offset inside a print position, whether inside one of its escape sequences or inside its grapheme
+//! (say, between a letter and its combining accent), resolves to the start of that print position.
+//! A column inside a wide grapheme resolves to the start of the print position which occupies it.
+//!
+//! Columns are counted the same way throughout: each print position takes its display width, except that a tab
+//! takes the columns up to the next tab stop, which are every 8 columns from the start of its line.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::ops::Range;
+
+#[cfg(feature = "alloc")]
+use crate::lines::is_line_terminator;
+#[cfg(feature = "alloc")]
+use crate::Segmenter;
+use crate::{PrintPosition, TAB_STOP};
+
+#[cfg(test)]
+mod tests;
+
+/// A print position boundary, in each of the coordinate systems.  Returned by [Coordinates].
+///
+/// All are offsets from the start of the string.  The column is the sum of the widths of the
+/// print positions before the boundary, with tabs expanded to the next tab stop of their line, across line breaks
+/// (see [lines](crate::lines) to measure lines separately).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coordinate {
+    /// UTF-8 byte offset.
+    pub byte: usize,
+    /// UTF-16 code unit offset.
+    pub utf16: usize,
+    /// `char` (Unicode scalar value) offset.
+    pub char: usize,
+    /// Print position index.
+    pub index: usize,
+    /// Display column.
+    pub column: usize,
+}
+
+/// An index of the print position boundaries of a string, for converting between coordinate systems.
+///
+/// Building it segments the whole string, each conversion is then a binary search.
+///
+/// ```rust
+/// use print_positions::coordinates::Coordinates;
+///
+/// let content = "\u{1b}[31m\u{4e2d}\u{1b}[0m\u{1f600}e\u{301}";
+/// let coordinates = Coordinates::new(content);
+///
+/// let emoji = coordinates.at_index(1).unwrap();
+/// assert_eq!(emoji.byte, 12);
+/// assert_eq!(emoji.char, 10);
+/// assert_eq!(emoji.column, 2);    // after the double width ideograph
+///
+/// assert_eq!(coordinates.at_byte(2), coordinates.at_index(0));     // inside the escape sequence
+/// assert_eq!(coordinates.at_utf16(11), Some(emoji));                // between the emoji's surrogates
+/// assert_eq!(coordinates.at_column(3), Some(emoji));                // second half of the emoji
+/// assert_eq!(coordinates.at_char(12).unwrap().index, 2);             // before the combining accent
+/// assert_eq!(coordinates.at_index(2).unwrap().utf16, 12);            // the emoji is 2 UTF-16 code units
+/// assert_eq!(coordinates.end().column, 5);
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Coordinates<'a> {
+    string: &'a str,
+    // the start of each print position, then the end of the string
+    boundaries: Vec<Coordinate>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Coordinates<'a> {
+    /// Index `s`, measuring columns with the default [Segmenter].
+    pub fn new(s: &'a str) -> Self {
+        Coordinates::with_segmenter(s, Segmenter::new())
+    }
+
+    /// Index `s`, segmenting and measuring it with `segmenter`.
+    pub fn with_segmenter(s: &'a str, segmenter: Segmenter<'_>) -> Self {
+        let mut boundaries = Vec::new();
+        let mut here = Coordinate::default();
+        let mut line_start = 0;
+        for p in segmenter.print_position_info(s) {
+            boundaries.push(here);
+            for c in p.as_str().chars() {
+                here.utf16 += c.len_utf16();
+                here.char += 1;
+            }
+            here.byte = p.range().end;
+            here.index += 1;
+            here.column += columns(&p, here.column - line_start);
+            if is_line_terminator(p.grapheme()) {
+                line_start = here.column;
+            }
+        }
+        boundaries.push(here);
+        Coordinates {
+            string: s,
+            boundaries,
+        }
+    }
+
+    /// The indexed string.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.string
+    }
+
+    /// Number of print positions.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.boundaries.len() - 1
+    }
+
+    /// Whether there are no print positions (and so no text).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The end of the string.
+    #[inline]
+    pub fn end(&self) -> Coordinate {
+        self.boundaries[self.len()]
+    }
+
+    /// The start of the print position containing UTF-8 byte `offset`, or the end of the string at its length.
+    /// `None` past the end.
+    pub fn at_byte(&self, offset: usize) -> Option<Coordinate> {
+        self.find(offset, |c| c.byte)
+    }
+
+    /// The start of the print position containing UTF-16 code unit `offset`,
+    /// or the end of the string at its length.  `None` past the end.
+    pub fn at_utf16(&self, offset: usize) -> Option<Coordinate> {
+        self.find(offset, |c| c.utf16)
+    }
+
+    /// The start of the print position containing `char` `offset`, or the end of the string at its length.
+    /// `None` past the end.
+    pub fn at_char(&self, offset: usize) -> Option<Coordinate> {
+        self.find(offset, |c| c.char)
+    }
+
+    /// The start of print position `index`, or the end of the string if `index` is the number of print positions.
+    /// `None` past the end.
+    pub fn at_index(&self, index: usize) -> Option<Coordinate> {
+        self.boundaries.get(index).copied()
+    }
+
+    /// The start of the print position occupying display column `column`,
+    /// or the end of the string at its width.  `None` past the end.
+    ///
+    /// Zero width print positions occupy no column, so the print position found is the one after them.
+    /// A tab occupies the columns up to the next tab stop, as in [position_at_column].
+    pub fn at_column(&self, column: usize) -> Option<Coordinate> {
+        self.find(column, |c| c.column)
+    }
+
+    // the last boundary at or before `offset`
+    fn find(&self, offset: usize, key: impl Fn(&Coordinate) -> usize) -> Option<Coordinate> {
+        if offset > key(&self.end()) {
+            return None;
+        }
+        let after = self.boundaries.partition_point(|c| key(c) <= offset);
+        Some(self.boundaries[after - 1])
+    }
+}
+
+/// The print position found at a display column by [position_at_column].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Hit {
+    /// Print position index.
+    pub index: usize,
+    /// Start and end + 1 byte offsets of the print position, including its escape sequences.
+    pub byte_range: Range<usize>,
+    /// Column within the print position: 0 for its first column, 1 for the second half of a double width grapheme,
+    /// and so on across the columns a tab skips.
+    pub offset_within_glyph: usize,
+}
+
+/// The print position displayed at column `column` of line `s`, or `None` if the line doesn't reach that column.
+///
+/// For hit-testing a mouse click.  Columns count from 0 at the start of `s`, which is taken to be a single line.
+/// Escape sequences take no columns, nor do zero width graphemes, so no column hits them.
+/// A tab occupies the columns up to the next tab stop, which are every 8 columns.
+///
+/// ```rust
+/// use print_positions::coordinates::{position_at_column, Hit};
+///
+/// let line = "\u{1b}[1m\u{4e2d}\u{1b}[0m\tx";
+/// assert_eq!(
+///     position_at_column(line, 1),
+///     Some(Hit { index: 0, byte_range: 0..11, offset_within_glyph: 1 })
+/// );
+/// assert_eq!(position_at_column(line, 5).map(|hit| hit.index), Some(1));  // the tab, from column 2 to 7
+/// assert_eq!(position_at_column(line, 8).map(|hit| hit.index), Some(2));
+/// assert_eq!(position_at_column(line, 9), None);
+/// ```
+pub fn position_at_column(s: &str, column: usize) -> Option<Hit> {
+    let mut start = 0;
+    for (index, p) in crate::print_position_info(s).enumerate() {
+        let width = columns(&p, start);
+        if column < start + width {
+            return Some(Hit {
+                index,
+                byte_range: p.range(),
+                offset_within_glyph: column - start,
+            });
+        }
+        start += width;
+    }
+    None
+}
+
+// number of columns `p` takes, starting at column `column` of its line: up to the next tab stop for a tab,
+// its width for anything else
+fn columns(p: &PrintPosition, column: usize) -> usize {
+    match p.grapheme() {
+        "\t" => (column / TAB_STOP + 1) * TAB_STOP - column,
+        _ => p.width(),
+    }
+}
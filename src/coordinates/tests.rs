@@ -0,0 +1,133 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[cfg(feature = "alloc")]
+use crate::print_positions;
+
+#[cfg(feature = "alloc")]
+const CONTENT: &str =
+    "a\u{1b}[1m\u{1f468}\u{200d}\u{1f469}\u{1b}[0m\n\u{4e2d}\u{200b}e\u{301}\u{1b}]8;;x\u{1b}\\";
+
+#[cfg(feature = "alloc")]
+#[test]
+fn every_offset() -> Result<()> {
+    let coordinates = Coordinates::new(CONTENT);
+    let starts: Vec<_> = print_positions(CONTENT).map(|(start, _)| start).collect();
+    assert_eq!(coordinates.len(), starts.len());
+
+    // each conversion lands on the start of the print position containing the offset
+    let mut utf16 = 0;
+    for (char, (byte, c)) in CONTENT.char_indices().enumerate() {
+        let index = starts.partition_point(|&start| start <= byte) - 1;
+        let expected = coordinates.at_index(index).context("index")?;
+        assert_eq!(expected.byte, starts[index]);
+        assert_eq!(coordinates.at_byte(byte), Some(expected), "byte {byte}");
+        assert_eq!(coordinates.at_char(char), Some(expected), "char {char}");
+        for unit in 0..c.len_utf16() {
+            assert_eq!(
+                coordinates.at_utf16(utf16 + unit),
+                Some(expected),
+                "utf16 {utf16}"
+            );
+        }
+        utf16 += c.len_utf16();
+    }
+
+    let end = coordinates.end();
+    assert_eq!(
+        end,
+        Coordinate {
+            byte: CONTENT.len(),
+            utf16,
+            char: CONTENT.chars().count(),
+            index: starts.len(),
+            column: crate::display_width(CONTENT),
+        }
+    );
+    assert_eq!(coordinates.at_byte(CONTENT.len()), Some(end));
+    assert_eq!(coordinates.at_byte(CONTENT.len() + 1), None);
+    assert_eq!(coordinates.at_index(starts.len() + 1), None);
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn columns() -> Result<()> {
+    let coordinates = Coordinates::new(CONTENT);
+    let index = |column| coordinates.at_column(column).map(|c| c.index);
+    assert_eq!(index(0), Some(0)); // "a"
+    assert_eq!(index(1), Some(1)); // family emoji
+    assert_eq!(index(2), Some(1));
+    assert_eq!(index(3), Some(3)); // ideograph, after the line feed
+    assert_eq!(index(5), Some(5)); // after the zero width space
+    assert_eq!(index(6), Some(6)); // the end
+    assert_eq!(index(7), None);
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn empty() -> Result<()> {
+    let coordinates = Coordinates::new("");
+    assert!(coordinates.is_empty());
+    assert_eq!(coordinates.at_byte(0), Some(Coordinate::default()));
+    assert_eq!(coordinates.at_column(0), Some(Coordinate::default()));
+    assert_eq!(coordinates.at_char(1), None);
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn segmenter() -> Result<()> {
+    let segmenter = Segmenter::new().attach_zero_width(true);
+    let coordinates = Coordinates::with_segmenter("\u{200b}ab", segmenter);
+    assert_eq!(coordinates.len(), 2);
+    assert_eq!(coordinates.at_char(1).map(|c| c.index), Some(0));
+    Ok(())
+}
+
+#[test]
+fn hits() -> Result<()> {
+    let line = "a\u{1f600}\u{200b}\t\u{1b}[31mb\tc\u{1b}[0m";
+    let hits: Vec<_> = (0..18)
+        .map(|column| {
+            position_at_column(line, column).map(|hit| (hit.index, hit.offset_within_glyph))
+        })
+        .collect();
+    let mut expected = vec![Some((0, 0)), Some((1, 0)), Some((1, 1))];
+    expected.extend((0..5).map(|offset| Some((3, offset)))); // tab from column 3, after the zero width space
+    expected.push(Some((4, 0)));
+    expected.extend((0..7).map(|offset| Some((5, offset))));
+    expected.push(Some((6, 0)));
+    expected.push(None);
+    assert_eq!(hits, expected);
+
+    let c = position_at_column(line, 16).context("c")?;
+    assert_eq!(&line[c.byte_range], "c\u{1b}[0m");
+    assert_eq!(position_at_column("", 0), None);
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn tabs_same_in_both() -> Result<()> {
+    let line = "a\u{1b}[1m\tb\u{4e2d}\t\u{200b}c\t";
+    let coordinates = Coordinates::new(line);
+    assert_eq!(coordinates.end().column, 24);
+    for column in 0..24 {
+        let hit = position_at_column(line, column).context("hit")?;
+        let found = coordinates.at_column(column).context("found")?;
+        assert_eq!(hit.index, found.index, "column {column}");
+        assert_eq!(hit.byte_range.start, found.byte, "column {column}");
+    }
+    assert_eq!(position_at_column(line, 24), None);
+
+    // tab stops count from the start of each line
+    let coordinates = Coordinates::new("abc\n\tx");
+    assert_eq!(coordinates.at_index(5).map(|c| c.column), Some(11));
+    assert_eq!(coordinates.at_column(10).map(|c| c.index), Some(4));
+    Ok(())
+}
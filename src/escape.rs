@@ -0,0 +1,96 @@
+//! Parsing of the ANSI escape sequences found in print positions.
+//!
+//! The print position iterators only need to know where escape sequences start and end.
+//! Code that interprets them (styles, hyperlinks, screen rendering) uses this parser to pick them apart.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+#[cfg(test)]
+mod tests;
+
+/// One escape sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Escape<'a> {
+    /// Control Sequence Introducer: `ESC [ <params> <final byte>`.
+    /// `params` includes any intermediate bytes.
+    Csi { params: &'a str, final_byte: u8 },
+    /// Operating System Command: `ESC ] <data>`, terminated by BEL or `ESC \`.
+    Osc(&'a str),
+    /// Any other escape sequence, `ESC <byte>`.  `ESC c` is a full reset (RIS).
+    Simple(u8),
+}
+
+/// Iterator over the escape sequences found in a string.
+///
+/// Anything outside an escape sequence is skipped, so this can be applied to a whole print position as well as
+/// its prefix or suffix escapes.
+pub(crate) struct Escapes<'a> {
+    rest: &'a str,
+}
+
+/// Find the escape sequences in `s`.
+pub(crate) fn escapes(s: &str) -> Escapes<'_> {
+    Escapes { rest: s }
+}
+
+impl<'a> Iterator for Escapes<'a> {
+    type Item = Escape<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.rest.find('\x1b')?;
+        let (escape, len) = parse(&self.rest[start..]);
+        self.rest = &self.rest[start + len..];
+        Some(escape)
+    }
+}
+
+/// Parse the escape sequence at the start of `s`, which must begin with ESC.
+///
+/// Returns the escape and its length in bytes.  Like [PrintPositions](crate::PrintPositions), this steps through
+/// `s` a grapheme at a time, so malformed or unterminated sequences end the same way the iterator ends them.
+pub(crate) fn parse(s: &str) -> (Escape<'_>, usize) {
+    debug_assert!(s.starts_with('\x1b'));
+    let mut graphemes = s.grapheme_indices(true).skip(1);
+    let Some((_, second)) = graphemes.next() else {
+        return (Escape::Simple(0), s.len());
+    };
+    let body = 1 + second.len();
+    match second.as_bytes()[0] {
+        b'[' => {
+            for (i, g) in graphemes {
+                let b = g.as_bytes()[0];
+                if !(0x20..=0x3f).contains(&b) {
+                    // 0x40..=0x7e is the final byte, anything else ends a malformed sequence
+                    let final_byte = if (0x40..=0x7e).contains(&b) { b } else { 0 };
+                    return (
+                        Escape::Csi {
+                            params: &s[body..i],
+                            final_byte,
+                        },
+                        i + g.len(),
+                    );
+                }
+            }
+            (
+                Escape::Csi {
+                    params: &s[body..],
+                    final_byte: 0,
+                },
+                s.len(),
+            )
+        }
+        b']' => {
+            let mut esc_seen = None;
+            for (i, g) in graphemes {
+                match (g.as_bytes()[0], esc_seen) {
+                    (0x07, None) => return (Escape::Osc(&s[body..i]), i + g.len()),
+                    (0x1b, _) => esc_seen = Some(i),
+                    (b'\\', Some(esc)) => return (Escape::Osc(&s[body..esc]), i + g.len()),
+                    _ => esc_seen = None,
+                }
+            }
+            (Escape::Osc(&s[body..]), s.len())
+        }
+        b => (Escape::Simple(b), body),
+    }
+}
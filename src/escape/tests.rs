@@ -0,0 +1,84 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[test]
+fn well_formed() -> Result<()> {
+    assert_eq!(
+        parse("\u{1b}[1;31mx"),
+        (
+            Escape::Csi {
+                params: "1;31",
+                final_byte: b'm'
+            },
+            7
+        )
+    );
+    assert_eq!(
+        parse("\u{1b}]8;;http://x.org\u{7}x"),
+        (Escape::Osc("8;;http://x.org"), 18)
+    );
+    assert_eq!(parse("\u{1b}]0;t\u{1b}\\x"), (Escape::Osc("0;t"), 7));
+    assert_eq!(parse("\u{1b}cx"), (Escape::Simple(b'c'), 2));
+    Ok(())
+}
+
+#[test]
+fn unterminated() -> Result<()> {
+    assert_eq!(parse("\u{1b}"), (Escape::Simple(0), 1));
+    assert_eq!(
+        parse("\u{1b}[1;"),
+        (
+            Escape::Csi {
+                params: "1;",
+                final_byte: 0
+            },
+            4
+        )
+    );
+    assert_eq!(parse("\u{1b}]0;title"), (Escape::Osc("0;title"), 9));
+    Ok(())
+}
+
+#[test]
+fn graphemes_end_malformed_sequences() -> Result<()> {
+    // the combining mark joins the '1', a parameter byte, so it doesn't end the CSI
+    assert_eq!(
+        parse("\u{1b}[1\u{301}mx"),
+        (
+            Escape::Csi {
+                params: "1\u{301}",
+                final_byte: b'm'
+            },
+            6
+        )
+    );
+    // ESC joined by a combining mark doesn't start the OSC terminator
+    assert_eq!(
+        parse("\u{1b}]0;t\u{1b}\u{301}\\\u{7}"),
+        (Escape::Osc("0;t\u{1b}\u{301}\\"), 10)
+    );
+    // and a combining mark joins the second char of a simple escape
+    assert_eq!(parse("\u{1b}c\u{301}x"), (Escape::Simple(b'c'), 4));
+    Ok(())
+}
+
+#[test]
+fn same_ends_as_print_positions() -> Result<()> {
+    for s in [
+        "\u{1b}[1\u{301}mx",
+        "\u{1b}]0;t\u{1b}\u{301}\\\u{7}x",
+        "\u{1b}c\u{301}x",
+        "\u{1b}[\u{4e2d}x",
+    ] {
+        let (_, len) = parse(s);
+        let grapheme = crate::print_position_info(s)
+            .next()
+            .expect("a print position")
+            .grapheme();
+        assert_eq!(&s[len..], grapheme, "{s:?}");
+    }
+    Ok(())
+}
@@ -0,0 +1,191 @@
+//! C interface, built with feature `ffi`.
+//!
+//! Strings are passed as a pointer and a length in bytes, they needn't be NUL-terminated but must be valid UTF-8.
+//! A NULL pointer with length 0 is the empty string.
+//! Each function returns a [PpStatus], and its results through pointers supplied by the caller, which are only
+//! written when it returns [PpStatus::Ok] (or, for the length of the output, [PpStatus::BufferTooSmall]).
+//! Text is copied into a buffer supplied by the caller, without a terminating NUL.
+//! A panic doesn't unwind into the caller, the function returns [PpStatus::Panic] instead.
+//!
+//! The header, `include/print_positions.h`, is generated by [cbindgen](https://github.com/mozilla/cbindgen):
+//! `cbindgen --config cbindgen.toml --output include/print_positions.h`.  CI checks that it's up to date.
+//! To link the library into a C program, build it as a static or dynamic library:
+//! `cargo rustc --release --features ffi --crate-type staticlib` (or `cdylib`).
+//!
+//! ```c
+//! #include "print_positions.h"
+//!
+//! const char *s = "\x1b[1m\xe4\xb8\xad\x1b[0m!";
+//! size_t width;
+//! if (pp_display_width((const uint8_t *)s, strlen(s), &width) == PP_STATUS_OK) {
+//!     printf("%zu columns\n", width);     // 3 columns
+//! }
+//! ```
+
+use core::{ptr, slice, str};
+use std::panic::{catch_unwind, UnwindSafe};
+
+use crate::text::{strip_escapes, truncate};
+use crate::{display_width, next_boundary, print_positions};
+
+#[cfg(test)]
+mod tests;
+
+/// Outcome of a call through the C interface.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PpStatus {
+    /// Success, results have been written.
+    Ok = 0,
+    /// A pointer argument was NULL (other than a string or buffer of length 0).
+    NullPointer = 1,
+    /// The input string isn't valid UTF-8.
+    InvalidUtf8 = 2,
+    /// The output buffer is too small.  The length it needs has been written.
+    BufferTooSmall = 3,
+    /// An offset argument is past the end of the input string.
+    OutOfBounds = 4,
+    /// An internal error: the function panicked.
+    Panic = 5,
+}
+
+/// Count the print positions in `s[..len]`, storing the count in `*count`.
+///
+/// # Safety
+/// `s` must point to `len` readable bytes and `count` must be valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn pp_count(s: *const u8, len: usize, count: *mut usize) -> PpStatus {
+    status(|| {
+        let s = input(s, len)?;
+        store(count, print_positions(s).count())
+    })
+}
+
+/// Measure the number of columns `s[..len]` occupies on the screen, storing it in `*width`.
+///
+/// # Safety
+/// `s` must point to `len` readable bytes and `width` must be valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn pp_display_width(s: *const u8, len: usize, width: *mut usize) -> PpStatus {
+    status(|| {
+        let s = input(s, len)?;
+        store(width, display_width(s))
+    })
+}
+
+/// Find the first print position boundary after byte `offset` of `s[..len]`, storing it in `*next`.
+/// At the end of the string, that's `len`.
+///
+/// # Safety
+/// `s` must point to `len` readable bytes and `next` must be valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn pp_next_boundary(
+    s: *const u8,
+    len: usize,
+    offset: usize,
+    next: *mut usize,
+) -> PpStatus {
+    status(|| {
+        let s = input(s, len)?;
+        if offset > s.len() {
+            return Err(PpStatus::OutOfBounds);
+        }
+        store(next, next_boundary(s, offset).unwrap_or(s.len()))
+    })
+}
+
+/// Copy `s[..len]` without its escape sequences into `buf[..buf_len]`, storing the length of the copy in `*written`.
+///
+/// If the copy doesn't fit, nothing is copied, `*written` is the length it needs and the result is
+/// `PP_STATUS_BUFFER_TOO_SMALL`.  `buf` may be NULL if `buf_len` is 0, to ask for the length.
+///
+/// # Safety
+/// `s` must point to `len` readable bytes, `buf` to `buf_len` writable bytes (not overlapping `s`),
+/// and `written` must be valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn pp_strip_escapes(
+    s: *const u8,
+    len: usize,
+    buf: *mut u8,
+    buf_len: usize,
+    written: *mut usize,
+) -> PpStatus {
+    status(|| {
+        let s = input(s, len)?;
+        output(&strip_escapes(s), buf, buf_len, written)
+    })
+}
+
+/// Copy as many leading print positions of `s[..len]` as fit in `width` columns into `buf[..buf_len]`,
+/// closing any style or hyperlink still open at the cut, like `truncate` in the crate's `text` module,
+/// and store the length of the copy in `*written`.
+///
+/// If the copy doesn't fit, nothing is copied, `*written` is the length it needs and the result is
+/// `PP_STATUS_BUFFER_TOO_SMALL`.  `buf` may be NULL if `buf_len` is 0, to ask for the length.
+///
+/// # Safety
+/// `s` must point to `len` readable bytes, `buf` to `buf_len` writable bytes (not overlapping `s`),
+/// and `written` must be valid for writing a `size_t`.
+#[no_mangle]
+pub unsafe extern "C" fn pp_truncate(
+    s: *const u8,
+    len: usize,
+    width: usize,
+    buf: *mut u8,
+    buf_len: usize,
+    written: *mut usize,
+) -> PpStatus {
+    status(|| {
+        let s = input(s, len)?;
+        output(&truncate(s, width), buf, buf_len, written)
+    })
+}
+
+// the result of `f`, which mustn't unwind across the C interface
+fn status(f: impl FnOnce() -> Result<(), PpStatus> + UnwindSafe) -> PpStatus {
+    match catch_unwind(f) {
+        Ok(result) => result.err().unwrap_or(PpStatus::Ok),
+        Err(_) => PpStatus::Panic,
+    }
+}
+
+// the string the caller passed in
+unsafe fn input<'a>(s: *const u8, len: usize) -> Result<&'a str, PpStatus> {
+    if s.is_null() {
+        return if len == 0 {
+            Ok("")
+        } else {
+            Err(PpStatus::NullPointer)
+        };
+    }
+    str::from_utf8(slice::from_raw_parts(s, len)).map_err(|_| PpStatus::InvalidUtf8)
+}
+
+// a result for the caller
+unsafe fn store(out: *mut usize, value: usize) -> Result<(), PpStatus> {
+    if out.is_null() {
+        return Err(PpStatus::NullPointer);
+    }
+    out.write(value);
+    Ok(())
+}
+
+// text for the caller's buffer
+unsafe fn output(
+    text: &str,
+    buf: *mut u8,
+    buf_len: usize,
+    written: *mut usize,
+) -> Result<(), PpStatus> {
+    if buf.is_null() && buf_len > 0 {
+        return Err(PpStatus::NullPointer);
+    }
+    store(written, text.len())?;
+    if text.len() > buf_len {
+        return Err(PpStatus::BufferTooSmall);
+    }
+    if !text.is_empty() {
+        ptr::copy_nonoverlapping(text.as_ptr(), buf, text.len());
+    }
+    Ok(())
+}
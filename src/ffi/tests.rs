@@ -0,0 +1,172 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use core::ptr::{null, null_mut};
+
+const CONTENT: &str = "\u{1b}[1m\u{4e2d}\u{1b}[0m!e\u{301}";
+
+#[test]
+fn measures() -> Result<()> {
+    let (mut count, mut width) = (0, 0);
+    unsafe {
+        assert_eq!(
+            pp_count(CONTENT.as_ptr(), CONTENT.len(), &mut count),
+            PpStatus::Ok
+        );
+        assert_eq!(
+            pp_display_width(CONTENT.as_ptr(), CONTENT.len(), &mut width),
+            PpStatus::Ok
+        );
+    }
+    assert_eq!(count, 3);
+    assert_eq!(width, 4);
+    Ok(())
+}
+
+#[test]
+fn boundaries() -> Result<()> {
+    let mut found = vec![];
+    let mut offset = 0;
+    while offset < CONTENT.len() {
+        let status =
+            unsafe { pp_next_boundary(CONTENT.as_ptr(), CONTENT.len(), offset, &mut offset) };
+        assert_eq!(status, PpStatus::Ok);
+        found.push(offset);
+    }
+    assert_eq!(found, vec![11, 12, 15]);
+
+    let mut next = 0;
+    let status = unsafe { pp_next_boundary(CONTENT.as_ptr(), CONTENT.len(), 16, &mut next) };
+    assert_eq!(status, PpStatus::OutOfBounds);
+    Ok(())
+}
+
+#[test]
+fn boundaries_in_long_osc() -> Result<()> {
+    // an unterminated OSC sequence longer than 4 KiB is one print position, as in print_positions
+    let s = format!("\x1b]0;{}", "ab c ".repeat(2000));
+    for offset in [0, 4096, 9000, 9001] {
+        let mut next = 0;
+        let status = unsafe { pp_next_boundary(s.as_ptr(), s.len(), offset, &mut next) };
+        assert_eq!(status, PpStatus::Ok);
+        assert_eq!(next, s.len(), "next {offset}");
+    }
+    Ok(())
+}
+
+#[test]
+fn buffers() -> Result<()> {
+    let mut buf = [0u8; 8];
+    let mut written = 0;
+
+    // the length first, then the text
+    let status =
+        unsafe { pp_strip_escapes(CONTENT.as_ptr(), CONTENT.len(), null_mut(), 0, &mut written) };
+    assert_eq!((status, written), (PpStatus::BufferTooSmall, 7));
+    let status = unsafe {
+        pp_strip_escapes(
+            CONTENT.as_ptr(),
+            CONTENT.len(),
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut written,
+        )
+    };
+    assert_eq!(status, PpStatus::Ok);
+    assert_eq!(&buf[..written], "\u{4e2d}!e\u{301}".as_bytes());
+
+    let status = unsafe {
+        pp_truncate(
+            CONTENT.as_ptr(),
+            CONTENT.len(),
+            2,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut written,
+        )
+    };
+    assert_eq!((status, written), (PpStatus::BufferTooSmall, 11));
+    let mut buf = [0u8; 16];
+    let status = unsafe {
+        pp_truncate(
+            CONTENT.as_ptr(),
+            CONTENT.len(),
+            2,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut written,
+        )
+    };
+    assert_eq!(status, PpStatus::Ok);
+    assert_eq!(&buf[..written], "\u{1b}[1m\u{4e2d}\u{1b}[0m".as_bytes());
+    Ok(())
+}
+
+#[test]
+fn invalid_arguments() -> Result<()> {
+    let mut out = 42;
+    let invalid = b"a\xffb";
+    unsafe {
+        assert_eq!(pp_count(null(), 1, &mut out), PpStatus::NullPointer);
+        assert_eq!(
+            pp_count(CONTENT.as_ptr(), CONTENT.len(), null_mut()),
+            PpStatus::NullPointer
+        );
+        assert_eq!(
+            pp_display_width(invalid.as_ptr(), invalid.len(), &mut out),
+            PpStatus::InvalidUtf8
+        );
+        assert_eq!(
+            pp_strip_escapes(CONTENT.as_ptr(), CONTENT.len(), null_mut(), 8, &mut out),
+            PpStatus::NullPointer
+        );
+        assert_eq!(
+            pp_truncate(invalid.as_ptr(), invalid.len(), 1, null_mut(), 0, &mut out),
+            PpStatus::InvalidUtf8
+        );
+    }
+    assert_eq!(out, 42, "nothing written on failure");
+
+    // an empty string needs no bytes, nor a pointer
+    unsafe {
+        assert_eq!(pp_count("".as_ptr(), 0, &mut out), PpStatus::Ok);
+        assert_eq!(out, 0);
+        out = 42;
+        assert_eq!(pp_display_width(null(), 0, &mut out), PpStatus::Ok);
+        assert_eq!(out, 0);
+        assert_eq!(
+            pp_strip_escapes(null(), 0, null_mut(), 0, &mut out),
+            PpStatus::Ok
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn panics_stay_inside() -> Result<()> {
+    assert_eq!(status(|| panic!("oops")), PpStatus::Panic);
+    assert_eq!(status(|| Err(PpStatus::OutOfBounds)), PpStatus::OutOfBounds);
+    Ok(())
+}
+
+#[test]
+fn header() -> Result<()> {
+    // regenerate with cbindgen when the interface changes, CI checks that the header is up to date
+    let header = include_str!("../../include/print_positions.h");
+    for f in [
+        "pp_count(",
+        "pp_display_width(",
+        "pp_next_boundary(",
+        "pp_strip_escapes(",
+        "pp_truncate(",
+        "PP_STATUS_BUFFER_TOO_SMALL",
+        "PP_STATUS_OUT_OF_BOUNDS",
+        "PP_STATUS_PANIC",
+    ] {
+        assert!(header.contains(f), "{f} missing from header");
+    }
+    Ok(())
+}
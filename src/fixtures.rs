@@ -0,0 +1,54 @@
+//! Test fixtures shared by the modules' tests.
+
+// pieces of text which interact with their neighbors when segmented
+pub(crate) const PIECES: &[&str] = &[
+    "a",
+    "ab",
+    "m",
+    "[",
+    "]",
+    " ",
+    "lorem ipsum",
+    "\u{301}",
+    "\u{4e2d}",
+    "\u{1f1fa}",
+    "\u{200d}",
+    "\u{1f468}",
+    "#",
+    "\u{fe0f}\u{20e3}",
+    "\r",
+    "\n",
+    "\n",
+    "\n",
+    "\u{1b}[31m",
+    "\u{1b}[1;4m",
+    "\u{1b}[0m",
+    "\u{1b}[m",
+    "0m",
+    "\u{1b}c",
+    "\u{1b}[",
+    "\u{1b}",
+    "\u{1b}]8;;http://x.org\u{1b}\\",
+    "\u{1b}]0;",
+    "\u{7}",
+];
+
+// deterministic pseudo-random numbers (xorshift), so failures can be reproduced
+pub(crate) struct Random(pub(crate) u64);
+
+impl Random {
+    // a number in 0..n
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+
+    // a string of `pieces` random PIECES
+    pub(crate) fn text(&mut self, pieces: usize) -> String {
+        (0..pieces)
+            .map(|_| PIECES[self.below(PIECES.len())])
+            .collect()
+    }
+}
@@ -0,0 +1,255 @@
+//! Render text containing ANSI escape sequences as HTML.
+//!
+//! Colors and emphasis selected by SGR escape sequences become `<span>` elements, styled either with
+//! inline CSS or with CSS classes.  OSC 8 hyperlinks become `<a>` elements, if their URI scheme is one of
+//! `http`, `https`, `mailto` or `file` (so a `javascript:` link in a log can't run script in the page).
+//! Other escape sequences are dropped.
+//!
+//! The output is an HTML fragment with newlines preserved, intended to go inside a `<pre>` element.
+//!
+//! ```rust
+//! use print_positions::html::{to_html, Html};
+//!
+//! let log = "ok \u{1b}[1;32mpassed\u{1b}[0m <3>";
+//! assert_eq!(
+//!     to_html(log),
+//!     r#"ok <span style="color:#00cd00;font-weight:bold">passed</span> &lt;3&gt;"#
+//! );
+//! assert_eq!(
+//!     Html::with_classes("ansi").render(log),
+//!     r#"ok <span class="ansi-bold ansi-fg-2">passed</span> &lt;3&gt;"#
+//! );
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::print_position_info;
+use crate::rendition::Rendition;
+use crate::style::{Color, Style};
+
+#[cfg(test)]
+mod tests;
+
+/// Render `s` as HTML with inline CSS styles.  See [Html::render].
+pub fn to_html(s: &str) -> String {
+    Html::default().render(s)
+}
+
+/// HTML renderer configuration.
+///
+/// The default renderer styles text with inline CSS, using the xterm palette for indexed colors.
+#[derive(Clone, Debug, Default)]
+pub struct Html {
+    // CSS class prefix, if rendering with classes rather than inline styles.
+    class_prefix: Option<String>,
+}
+
+impl Html {
+    /// A renderer which styles text with CSS classes named `<prefix>-<attribute>`, so the page's style sheet can
+    /// choose the colors.
+    ///
+    /// The classes are `<prefix>-bold`, `-dim`, `-italic`, `-underline`, `-blink`, `-inverse`, `-hidden` and
+    /// `-strikethrough` for emphasis, and `<prefix>-fg-<n>` and `<prefix>-bg-<n>` for palette colors 0 - 255.
+    /// 24 bit colors are still rendered as inline styles.
+    pub fn with_classes(prefix: &str) -> Self {
+        Html {
+            class_prefix: Some(prefix.to_string()),
+        }
+    }
+
+    /// Render `s` as an HTML fragment.
+    ///
+    /// HTML special characters in the text are escaped, and the generated `<a>` and `<span>` elements are
+    /// properly nested: a hyperlink always encloses the spans of text within it.
+    /// Newlines and tabs are preserved, other control characters are dropped.
+    pub fn render(&self, s: &str) -> String {
+        let mut out = String::with_capacity(s.len());
+        let mut rendition = Rendition::default();
+        // what the markup written so far has open
+        let mut open_style = Style::default();
+        let mut open_link = None;
+
+        for p in print_position_info(s) {
+            rendition.apply(p.prefix_escapes());
+            let style = rendition.style;
+            let link = rendition.link.filter(|link| safe_uri(link.uri()));
+
+            let grapheme = p.grapheme();
+            if p.is_control() {
+                if grapheme == "\n" || grapheme == "\r\n" {
+                    out.push('\n');
+                } else if grapheme == "\t" {
+                    out.push('\t');
+                }
+            } else if p.has_grapheme() {
+                if open_link != link {
+                    self.close_span(&mut out, &mut open_style);
+                    if open_link.is_some() {
+                        out.push_str("</a>");
+                    }
+                    if let Some(link) = link {
+                        out.push_str("<a href=\"");
+                        escape_into(&mut out, link.uri());
+                        out.push_str("\">");
+                    }
+                    open_link = link;
+                }
+                if open_style != style {
+                    self.close_span(&mut out, &mut open_style);
+                    if !style.is_default() {
+                        self.open_span(&mut out, &style);
+                        open_style = style;
+                    }
+                }
+                escape_into(&mut out, grapheme);
+            }
+
+            rendition.apply(p.suffix_escapes());
+        }
+
+        self.close_span(&mut out, &mut open_style);
+        if open_link.is_some() {
+            out.push_str("</a>");
+        }
+        out
+    }
+
+    fn open_span(&self, out: &mut String, style: &Style) {
+        out.push_str("<span ");
+        match &self.class_prefix {
+            Some(prefix) => {
+                let mut classes = vec![];
+                for (on, name) in [
+                    (style.bold, "bold"),
+                    (style.dim, "dim"),
+                    (style.italic, "italic"),
+                    (style.underline, "underline"),
+                    (style.blink, "blink"),
+                    (style.inverse, "inverse"),
+                    (style.hidden, "hidden"),
+                    (style.strikethrough, "strikethrough"),
+                ] {
+                    if on {
+                        classes.push(format!("{prefix}-{name}"));
+                    }
+                }
+                let mut css = String::new();
+                for (color, layer, property) in [
+                    (style.foreground, "fg", "color"),
+                    (style.background, "bg", "background-color"),
+                ] {
+                    match color {
+                        Some(Color::Indexed(n)) => classes.push(format!("{prefix}-{layer}-{n}")),
+                        Some(rgb) => push_css(&mut css, property, &hex(rgb)),
+                        None => {}
+                    }
+                }
+                if !classes.is_empty() {
+                    let _ = write!(out, "class=\"{}\"", classes.join(" "));
+                }
+                if !css.is_empty() {
+                    if !classes.is_empty() {
+                        out.push(' ');
+                    }
+                    let _ = write!(out, "style=\"{css}\"");
+                }
+            }
+            None => {
+                let _ = write!(out, "style=\"{}\"", inline_css(style));
+            }
+        }
+        out.push('>');
+    }
+
+    fn close_span(&self, out: &mut String, open_style: &mut Style) {
+        if !open_style.is_default() {
+            out.push_str("</span>");
+            *open_style = Style::default();
+        }
+    }
+}
+
+fn inline_css(style: &Style) -> String {
+    let mut css = String::new();
+    let (mut fg, mut bg) = (style.foreground, style.background);
+    if style.inverse {
+        // default colors of a page are black on white
+        (fg, bg) = (
+            Some(bg.unwrap_or(Color::Rgb(0xff, 0xff, 0xff))),
+            Some(fg.unwrap_or(Color::Rgb(0, 0, 0))),
+        );
+    }
+    if let Some(fg) = fg {
+        push_css(&mut css, "color", &hex(fg));
+    }
+    if let Some(bg) = bg {
+        push_css(&mut css, "background-color", &hex(bg));
+    }
+    if style.bold {
+        push_css(&mut css, "font-weight", "bold");
+    }
+    if style.dim {
+        push_css(&mut css, "opacity", "0.5");
+    }
+    if style.italic {
+        push_css(&mut css, "font-style", "italic");
+    }
+    let decorations: Vec<_> = [
+        (style.underline, "underline"),
+        (style.strikethrough, "line-through"),
+        (style.blink, "blink"),
+    ]
+    .iter()
+    .filter_map(|(on, name)| on.then_some(*name))
+    .collect();
+    if !decorations.is_empty() {
+        push_css(&mut css, "text-decoration", &decorations.join(" "));
+    }
+    if style.hidden {
+        push_css(&mut css, "visibility", "hidden");
+    }
+    css
+}
+
+fn push_css(css: &mut String, property: &str, value: &str) {
+    if !css.is_empty() {
+        css.push(';');
+    }
+    let _ = write!(css, "{property}:{value}");
+}
+
+fn hex(color: Color) -> String {
+    let (r, g, b) = color.to_rgb();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+// Schemes of the URIs rendered as links.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "file"];
+
+// whether `uri` starts with one of the SAFE_SCHEMES.  Anything else, including a relative URI or a scheme
+// with characters a browser would ignore (say, a tab), isn't.
+fn safe_uri(uri: &str) -> bool {
+    uri.split_once(':').is_some_and(|(scheme, _)| {
+        SAFE_SCHEMES
+            .iter()
+            .any(|safe| scheme.eq_ignore_ascii_case(safe))
+    })
+}
+
+// append text to out, escaping HTML special characters
+fn escape_into(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+}
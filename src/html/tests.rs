@@ -0,0 +1,99 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[test]
+fn plain_text_is_escaped() -> Result<()> {
+    assert_eq!(
+        to_html("a<b> & \"c\" 'd'"),
+        "a&lt;b&gt; &amp; &quot;c&quot; &#39;d&#39;"
+    );
+    assert_eq!(to_html("line 1\r\nline\t2\x07\n"), "line 1\nline\t2\n");
+    assert_eq!(to_html(""), "");
+    Ok(())
+}
+
+#[test]
+fn spans_for_style_runs() -> Result<()> {
+    let input = "\x1b[31mred\x1b[1mbold\x1b[22;39m plain\x1b[3m";
+    assert_eq!(
+        to_html(input),
+        concat!(
+            r#"<span style="color:#cd0000">red</span>"#,
+            r#"<span style="color:#cd0000;font-weight:bold">bold</span>"#,
+            " plain"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn class_rendering() -> Result<()> {
+    let input = "\x1b[4;38;5;208;48;2;0;0;255mx\x1bcy";
+    assert_eq!(
+        Html::with_classes("t").render(input),
+        r#"<span class="t-underline t-fg-208" style="background-color:#0000ff">x</span>y"#
+    );
+    Ok(())
+}
+
+#[test]
+fn inverse_with_default_colors() -> Result<()> {
+    assert_eq!(
+        to_html("\x1b[7mx"),
+        r#"<span style="color:#ffffff;background-color:#000000">x</span>"#
+    );
+    Ok(())
+}
+
+#[test]
+fn hyperlinks_enclose_spans() -> Result<()> {
+    let input = concat!(
+        "see \x1b]8;;https://example.com/?a=1&b=2\x1b\\",
+        "\x1b[1mdocs\x1b[0m here",
+        "\x1b]8;;\x1b\\ \x1b[1mnow"
+    );
+    assert_eq!(
+        to_html(input),
+        concat!(
+            r#"see <a href="https://example.com/?a=1&amp;b=2">"#,
+            r#"<span style="font-weight:bold">docs</span> here</a> "#,
+            r#"<span style="font-weight:bold">now</span>"#
+        )
+    );
+
+    // link and style both change mid-span
+    let input = "\x1b[1ma\x1b]8;;http://x\x07b\x1b]8;;http://y\x07c";
+    assert_eq!(
+        to_html(input),
+        concat!(
+            r#"<span style="font-weight:bold">a</span>"#,
+            r#"<a href="http://x"><span style="font-weight:bold">b</span></a>"#,
+            r#"<a href="http://y"><span style="font-weight:bold">c</span></a>"#
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn unsafe_links_dropped() -> Result<()> {
+    for uri in [
+        "javascript:alert(1)",
+        "JavaScript:alert(1)",
+        "java\tscript:alert(1)",
+        " javascript:alert(1)",
+        "data:text/html,<script>alert(1)</script>",
+        "vbscript:x",
+        "relative/path",
+    ] {
+        let input = format!("\x1b]8;;{uri}\x1b\\click\x1b]8;;\x1b\\");
+        assert_eq!(to_html(&input), "click", "{uri:?}");
+    }
+    for uri in ["http://x", "HTTPS://x", "mailto:a@b.c", "file:///tmp/log"] {
+        let input = format!("\x1b]8;;{uri}\x1b\\click\x1b]8;;\x1b\\");
+        assert_eq!(to_html(&input), format!(r#"<a href="{uri}">click</a>"#));
+    }
+    Ok(())
+}
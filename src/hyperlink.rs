@@ -0,0 +1,104 @@
+//! OSC 8 hyperlinks: `ESC ] 8 ; <params> ; <uri> ST`.
+//!
+//! Terminals that support them render the text between the sequence opening a link and the
+//! sequence closing it (one with an empty URI) as a clickable link.
+//! [PrintPosition::hyperlink](crate::PrintPosition::hyperlink) tells which link a print position falls inside.
+//!
+//! ```rust
+//! use print_positions::print_position_info;
+//!
+//! let content = "see \u{1b}]8;;https://example.com\u{1b}\\here\u{1b}]8;;\u{1b}\\.";
+//! let linked: String = print_position_info(content)
+//!     .filter(|p| p.hyperlink().is_some())
+//!     .map(|p| p.grapheme())
+//!     .collect();
+//! assert_eq!(linked, "here");
+//! ```
+
+use core::fmt;
+
+use crate::escape::{escapes, Escape};
+
+/// The escape sequence that closes an open hyperlink.
+pub const CLOSE: &str = "\x1b]8;;\x1b\\";
+
+/// An OSC 8 hyperlink, borrowed from the escape sequence that opened it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Hyperlink<'a> {
+    params: &'a str,
+    uri: &'a str,
+}
+
+impl<'a> Hyperlink<'a> {
+    /// Parse the data of an OSC escape sequence (what's between `ESC ]` and the terminator).
+    ///
+    /// Returns `None` if it's not an OSC 8 sequence, `Some(None)` if it closes a link
+    /// and `Some(Some(link))` if it opens one.
+    pub(crate) fn parse(osc: &'a str) -> Option<Option<Hyperlink<'a>>> {
+        let (params, uri) = osc.strip_prefix("8;")?.split_once(';')?;
+        Some((!uri.is_empty()).then_some(Hyperlink { params, uri }))
+    }
+
+    /// The link target.
+    #[inline]
+    pub fn uri(&self) -> &'a str {
+        self.uri
+    }
+
+    /// The `:` separated `key=value` parameters of the link, often empty.
+    #[inline]
+    pub fn params(&self) -> &'a str {
+        self.params
+    }
+
+    /// The `id` parameter, which terminals use to recognize separated pieces of text as the same link.
+    ///
+    /// ```rust
+    /// # use print_positions::print_position_info;
+    /// let content = "\u{1b}]8;id=42;https://example.com\u{7}x";
+    /// let link = print_position_info(content).next().unwrap().hyperlink().unwrap();
+    /// assert_eq!(link.id(), Some("42"));
+    /// assert_eq!(link.uri(), "https://example.com");
+    /// ```
+    pub fn id(&self) -> Option<&'a str> {
+        self.params
+            .split(':')
+            .find_map(|param| param.strip_prefix("id="))
+    }
+
+    /// The escape sequence that opens this hyperlink.
+    ///
+    /// ```rust
+    /// # use print_positions::print_position_info;
+    /// let content = "\u{1b}]8;;https://example.com\u{7}x";
+    /// let link = print_position_info(content).next().unwrap().hyperlink().unwrap();
+    /// assert_eq!(link.open().to_string(), "\u{1b}]8;;https://example.com\u{1b}\\");
+    /// ```
+    pub fn open(&self) -> impl fmt::Display + 'a {
+        Open(*self)
+    }
+}
+
+struct Open<'a>(Hyperlink<'a>);
+
+impl fmt::Display for Open<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\x1b]8;{};{}\x1b\\", self.0.params, self.0.uri)
+    }
+}
+
+/// Update the open hyperlink for the escape sequences in `escapes_str`.
+pub(crate) fn apply<'a>(escapes_str: &'a str, link: &mut Option<Hyperlink<'a>>) {
+    for escape in escapes(escapes_str) {
+        match escape {
+            Escape::Osc(data) => {
+                if let Some(new_link) = Hyperlink::parse(data) {
+                    *link = new_link;
+                }
+            }
+            Escape::Simple(b'c') => *link = None,
+            _ => {}
+        }
+    }
+}
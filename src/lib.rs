@@ -40,12 +40,70 @@
 //! Sometimes you don't even need to access the character data itself, you just want to know how many visible
 //! columns it will consume on the screen, in order to align it with other text or within a fixed area on the screen.  See iterator [PrintPositions].
 //!
+//! And sometimes you need to take a print position apart again: the grapheme without its escape codes, or how wide it
+//! will render.  See iterator [PrintPositionInfo].
+//!
+//! ## `no_std`:
+//! Without its default feature `std`, the crate is `#![no_std]`.  The iterators, widths, boundaries, styles and lines
+//! only borrow the source string, so they need no allocator either.  Feature `alloc` adds the modules which build
+//! `String`s and `Vec`s (such as [text] and [styled]), feature `std` adds `io::Write` for [tracker::ColumnTracker].
+//!
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(test)]
+mod fixtures;
 #[cfg(test)]
+// the original test harness predates these lints
+#[allow(clippy::len_zero, clippy::useless_vec)]
 mod tests;
 
+#[cfg(feature = "bidi")]
+pub mod bidi;
+mod boundary;
+pub mod coordinates;
+mod escape;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "alloc")]
+pub mod html;
+pub mod hyperlink;
+pub mod lines;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+mod position;
+mod rendition;
+#[cfg(feature = "alloc")]
+pub mod rope;
+mod scan;
+#[cfg(feature = "alloc")]
+pub mod screen;
+mod segmenter;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+pub mod serialize;
+pub mod style;
+#[cfg(feature = "alloc")]
+pub mod styled;
+#[cfg(feature = "alloc")]
+pub mod table;
+#[cfg(feature = "alloc")]
+pub mod text;
+#[cfg(feature = "alloc")]
+pub mod tracker;
+pub mod width;
+
+pub use boundary::{is_boundary, next_boundary, prev_boundary};
+pub use position::{print_position_info, PrintPosition, PrintPositionInfo};
+pub use width::{display_width, Segmenter};
+
 use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
 
+/// Columns between tab stops, for the modules which expand tabs: on a screen, in coordinates and when tracking columns.
+pub(crate) const TAB_STOP: usize = 8;
+
 /// This iterator identifies print positions in the source string and returns start and end offsets of 
 /// the data rather than the data itself.
 /// See [PrintPositionData] if you want to iterate through the data instead.
@@ -54,6 +112,7 @@ use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
 /// and any ANSI escape codes found between graphemes in the source.  The ANSI escape codes will generally *preceed*
 /// the grapheme (since these codes change the rendering of characters that follow), but sometimes will *follow* the
 /// grapheme (for the few codes that reset special graphic rendering).
+/// Escape codes at the very end of the string, with no grapheme following, are appended to the last print position.
 /// 
 /// ```rust
 /// use print_positions::print_positions;
@@ -76,8 +135,14 @@ pub struct PrintPositions<'a> {
     cur_offset: usize,
     // offset of the first unexamined char
     next_offset: usize,
-    // wrapped grapheme (== extended grapheme cluster) iterator
+    // wrapped grapheme (== extended grapheme cluster) iterator, over the string from `gi_offset`.
+    // Only used (and only kept in step with `next_offset`) where the text isn't plain ASCII.
     gi_iterator: GraphemeIndices<'a>,
+    gi_offset: usize,
+    // end of the run of plain ASCII (no ESC) found by the last scan
+    plain_end: usize,
+    // measures zero width graphemes to attach to a neighboring print position, if that's wanted
+    attach_zero_width: Option<Segmenter<'a>>,
 }
 /// Factory method to create a new [PrintPositions] iterator
 ///
@@ -89,6 +154,9 @@ pub fn print_positions<'a>(s: &'a str) -> PrintPositions<'a> {
         cur_offset: 0,
         next_offset: 0,
         gi_iterator: iter,
+        gi_offset: 0,
+        plain_end: 0,
+        attach_zero_width: None,
     }
 }
 
@@ -116,96 +184,69 @@ impl<'a> Iterator for PrintPositions<'a> {
     /// in source string of current print position.
     type Item = (usize, usize);
 
+    #[inline]
     fn next(&mut self) -> Option<Self::Item> {
+        self.next_parts().map(|parts| (parts.start, parts.end))
+    }
+}
+
+/// Offsets of the parts of one print position within the source string.
+/// `start..grapheme_start` are the leading escapes, `grapheme_start..grapheme_end` the grapheme
+/// and `grapheme_end..end` any trailing escapes.  An escape-only print position has an empty grapheme at its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Parts {
+    pub(crate) start: usize,
+    pub(crate) grapheme_start: usize,
+    pub(crate) grapheme_end: usize,
+    pub(crate) end: usize,
+}
+
+impl<'a> PrintPositions<'a> {
+    /// Segment the next print position, reporting where its grapheme lies as well as its bounds.
+    pub(crate) fn next_parts(&mut self) -> Option<Parts> {
         if self.next_offset > self.string.len() {
             return None;
         };
 
-        enum EscapeState {
-            Normal,
-            EscapeSeen, // just saw an escape, start accumulating
-            CSISeen,    // 2nd char not terminal, continue accumulating
-            OSCSeen,    // operating system commmand, accumulate through ESC\.
-            OSCSeen1,   // in OSC, saw ESC, look for \
+        // Fast path: in a run of plain ASCII, each byte followed by another is a print position on its own,
+        // with no escape sequences and nothing to attach.  (Except CR LF, which is one grapheme.)
+        if self.next_offset >= self.plain_end {
+            self.plain_end = self.next_offset
+                + scan::plain_ascii_len(&self.string.as_bytes()[self.next_offset..]);
+        }
+        let at = self.next_offset;
+        if at < self.plain_end
+            && (at + 1 < self.plain_end || at + 1 == self.string.len())
+            && !self.string[at..].starts_with("\r\n")
+            && self.attach_zero_width.is_none()
+        {
+            self.next_offset += 1;
+            self.cur_offset = self.next_offset;
+            return Some(Parts {
+                start: at,
+                grapheme_start: at,
+                grapheme_end: at + 1,
+                end: at + 1,
+            });
         }
 
         let mut escape_state = EscapeState::Normal;
+        let mut grapheme_seen = false;
+        let mut grapheme_start = self.string.len();
+        let mut grapheme_end = self.string.len();
 
         while self.next_offset < self.string.len() {
-            let grap = self.gi_iterator.next().expect("already checked not at EOS");
-            debug_assert_eq!(
-                grap.0, self.next_offset,
-                "offset of retrieved grap (left) not at start of rest of string (right)",
-            );
+            let grap = self.next_grapheme();
             self.next_offset += grap.1.len();
 
-            let ascii_byte = grap.1.as_bytes()[0];
-
-            match escape_state {
-                EscapeState::Normal => {
-                    if ascii_byte == 0x1b {
-                        escape_state = EscapeState::EscapeSeen;
-                    } else {
-                        break; // terminate the grapheme
-                    }
-                }
-
-                EscapeState::EscapeSeen => match ascii_byte {
-                    b'[' => {
-                        escape_state = EscapeState::CSISeen;
-                    }
-                    b']' => {
-                        escape_state = EscapeState::OSCSeen;
-                    }
-                    0x40..=0x5F => {
-                        // terminate escape, but continue accumulating rest of print position
-                        escape_state = EscapeState::Normal;
-                    }
-                    _ => {
-                        debug_assert!(
-                            true, // don't actually fail fuzz testing, but document behavior for malformed escapes.
-                            "unexpected char {ascii_byte} following ESC, terminating escape"
-                        );
-                        escape_state = EscapeState::Normal;
-                    }
-                },
-
-                EscapeState::CSISeen => {
-                    if (0x40..=0x7e).contains(&ascii_byte) {
-                        // end of CSI, but continue accumulating
-                        escape_state = EscapeState::Normal;
-                    } else if (0x20..=0x3f).contains(&ascii_byte) { // accumulate CSI
-                    } else {
-                        debug_assert!(
-                            true, // don't actually fail fuzz testing, but document behavior for malformed escapes.
-                            "unexpected char {ascii_byte} in CSI sequence, terminating escape"
-                        );
-                        escape_state = EscapeState::Normal;
-                    }
-                }
-
-                EscapeState::OSCSeen => {
-                    if ascii_byte == 0x07 {
-                        // spec says BEL terminates seq (on some emulators)
-                        escape_state = EscapeState::Normal;
-                    } else if ascii_byte == 0x1b {
-                        escape_state = EscapeState::OSCSeen1;
-                    } // anything else stays in OSC accumulation
-                }
-
-                EscapeState::OSCSeen1 => {
-                    match ascii_byte {
-                        0x5c => {
-                            // backslash
-                            escape_state = EscapeState::Normal;
-                        }
-                        0x1b => {
-                            escape_state = EscapeState::OSCSeen1;
-                        }
-                        _ => {
-                            escape_state = EscapeState::OSCSeen;
-                        }
-                    }
+            match escape_state.advance(grap.1.as_bytes()[0]) {
+                Some(state) => escape_state = state,
+                None if self.attaches(grap.1) => {} // accumulate it, like an escape sequence
+                None => {
+                    grapheme_seen = true;
+                    grapheme_start = grap.0;
+                    grapheme_end = self.next_offset;
+                    break; // terminate the grapheme
                 }
             }
         }
@@ -220,43 +261,186 @@ impl<'a> Iterator for PrintPositions<'a> {
         while self.next_offset < self.string.len()
             && self.string.as_bytes()[self.next_offset] == 0x1b
         {
-            if self.next_offset + 2 <= self.string.len()
-                && self.string[self.next_offset..].starts_with("\x1bc")
-            {
-                self.gi_iterator.next();
-                let last = self.gi_iterator.next().expect("must be >=2");
-                self.next_offset += 1 + last.1.len();
-            } else if self.next_offset + 3 <= self.string.len()
-                && self.string[self.next_offset..].starts_with("\x1b[m")
-            {
-                self.gi_iterator.next();
-                self.gi_iterator.next();
-                let last = self.gi_iterator.next().expect("must be >=3");
-                self.next_offset += 2 + last.1.len();
-            } else if self.next_offset + 4 <= self.string.len()
-                && self.string[self.next_offset..].starts_with("\x1b[0m")
-            {
-                self.gi_iterator.next();
-                self.gi_iterator.next();
-                self.gi_iterator.next();
-                let last = self.gi_iterator.next().expect("must be >=4");
-                self.next_offset += 3 + last.1.len();
+            let rest = &self.string[self.next_offset..];
+            let len = if rest.starts_with("\x1bc") {
+                2
+            } else if rest.starts_with("\x1b[m") {
+                3
+            } else if rest.starts_with("\x1b[0m") {
+                4
             } else {
                 break; // ESC then something else.  Take it at the beginning of the next call.
+            };
+            let end = self.next_offset + len;
+            while self.next_offset < end {
+                self.next_offset += self.next_grapheme().1.len();
+            }
+        }
+
+        // If nothing but escape sequences (and zero width graphemes to attach) remain in the string,
+        // there's no grapheme for them to preceed.
+        // Rather than return them as a print position of their own (which would be invisible but still counted),
+        // append them to this one.
+        if grapheme_seen
+            && self.next_offset < self.string.len()
+            && (self.string.as_bytes()[self.next_offset] == 0x1b
+                || self.attach_zero_width.is_some())
+        {
+            self.sync_graphemes();
+            let mut lookahead = self.gi_iterator.clone();
+            let mut state = EscapeState::Normal;
+            if lookahead.by_ref().all(|(_, grap)| {
+                state
+                    .advance(grap.as_bytes()[0])
+                    .map(|s| state = s)
+                    .is_some()
+                    || self.attaches(grap)
+            }) {
+                self.gi_iterator = lookahead;
+                self.next_offset = self.string.len();
             }
         }
+
         // return everything between start and end offsets
         if self.next_offset <= self.cur_offset {
-            return None;
+            None
         } else {
-            let retval = (self.cur_offset, self.next_offset);
+            let retval = Parts {
+                start: self.cur_offset,
+                grapheme_start,
+                grapheme_end,
+                end: self.next_offset,
+            };
             // advance start to one beyond end of what we're returning
             self.cur_offset = self.next_offset;
-            return Some(retval);
+            Some(retval)
         }
     }
+
+    /// The grapheme at `next_offset` (which must not be the end of the string), and its offset.
+    #[inline]
+    fn next_grapheme(&mut self) -> (usize, &'a str) {
+        let at = self.next_offset;
+        let bytes = self.string.as_bytes();
+        // ASCII followed by ASCII (or nothing) is a grapheme on its own, except CR LF
+        if bytes[at].is_ascii()
+            && bytes
+                .get(at + 1)
+                .is_none_or(|&next| next.is_ascii() && (bytes[at], next) != (b'\r', b'\n'))
+        {
+            return (at, &self.string[at..at + 1]);
+        }
+        self.sync_graphemes();
+        let (offset, grapheme) = self.gi_iterator.next().expect("already checked not at EOS");
+        (self.gi_offset + offset, grapheme)
+    }
+
+    /// Restart the grapheme iterator at `next_offset`, if it was left behind by graphemes found without it.
+    ///
+    /// That's always just after an ASCII grapheme followed by more ASCII, where no grapheme boundary rule
+    /// looks back any further.
+    fn sync_graphemes(&mut self) {
+        if self.string.len() - self.gi_iterator.as_str().len() != self.next_offset {
+            self.gi_offset = self.next_offset;
+            self.gi_iterator =
+                UnicodeSegmentation::grapheme_indices(&self.string[self.next_offset..], true);
+        }
+    }
+
+    /// Whether `grapheme` is to be attached to a neighboring print position rather than being one of its own.
+    #[inline]
+    fn attaches(&self, grapheme: &str) -> bool {
+        self.attach_zero_width
+            .is_some_and(|segmenter| segmenter.is_zero_width(grapheme))
+    }
+}
+
+/// Recognizer for ANSI escape sequences, advanced one grapheme at a time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum EscapeState {
+    Normal,
+    EscapeSeen, // just saw an escape, start accumulating
+    CSISeen,    // 2nd char not terminal, continue accumulating
+    OSCSeen,    // operating system commmand, accumulate through ESC\.
+    OSCSeen1,   // in OSC, saw ESC, look for \
 }
 
+impl EscapeState {
+    /// Advance the recognizer past a grapheme, given the first byte of the grapheme.
+    ///
+    /// Returns the next state if the grapheme is part of an escape sequence,
+    /// or `None` if it's an ordinary grapheme found outside any escape sequence.
+    fn advance(self, ascii_byte: u8) -> Option<EscapeState> {
+        let next = match self {
+            EscapeState::Normal => {
+                if ascii_byte == 0x1b {
+                    EscapeState::EscapeSeen
+                } else {
+                    return None;
+                }
+            }
+
+            EscapeState::EscapeSeen => match ascii_byte {
+                b'[' => EscapeState::CSISeen,
+                b']' => EscapeState::OSCSeen,
+                // 0x40..=0x5F terminates the escape, but continue accumulating rest of print position.
+                // Anything else is malformed, also terminate the escape (but don't fail fuzz testing).
+                _ => EscapeState::Normal,
+            },
+
+            EscapeState::CSISeen => {
+                if (0x20..=0x3f).contains(&ascii_byte) {
+                    EscapeState::CSISeen // accumulate CSI
+                } else {
+                    // 0x40..=0x7e is end of CSI, but continue accumulating.
+                    // Anything else is malformed, terminate the escape (but don't fail fuzz testing).
+                    EscapeState::Normal
+                }
+            }
+
+            EscapeState::OSCSeen => match ascii_byte {
+                0x07 => EscapeState::Normal, // spec says BEL terminates seq (on some emulators)
+                0x1b => EscapeState::OSCSeen1,
+                _ => EscapeState::OSCSeen, // anything else stays in OSC accumulation
+            },
+
+            EscapeState::OSCSeen1 => match ascii_byte {
+                0x5c => EscapeState::Normal, // backslash
+                0x1b => EscapeState::OSCSeen1,
+                _ => EscapeState::OSCSeen,
+            },
+        };
+        Some(next)
+    }
+}
+
+/// Determine whether a print position contains a grapheme, or consists only of ANSI escape sequences.
+///
+/// The print position iterators attach escape sequences to a neighboring grapheme, so an escape-only
+/// print position is only returned for a string that contains no graphemes at all.
+/// Note that control characters such as `\n` *are* graphemes, even though they're not visible.
+///
+/// ```rust
+/// use print_positions::{has_grapheme, print_position_data};
+///
+/// let segs: Vec<_> = print_position_data("ab\u{1b}[31m").collect();
+/// assert_eq!(vec!("a", "b\u{1b}[31m"), segs);    // trailing escape attached to last grapheme
+/// assert!(segs.iter().all(|s| has_grapheme(s)));
+///
+/// let segs: Vec<_> = print_position_data("\u{1b}[31m").collect();
+/// assert_eq!(vec!("\u{1b}[31m"), segs);          // no grapheme to attach to
+/// assert!(!has_grapheme(segs[0]));
+/// ```
+pub fn has_grapheme(print_position: &str) -> bool {
+    let mut state = EscapeState::Normal;
+    for grap in UnicodeSegmentation::graphemes(print_position, true) {
+        match state.advance(grap.as_bytes()[0]) {
+            Some(s) => state = s,
+            None => return true,
+        }
+    }
+    false
+}
 
 /// This iterator returns "print position" data found in a string, as an immutable slice within the source string.  
 /// 
@@ -314,11 +498,7 @@ impl<'a> Iterator for PrintPositionData<'a> {
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if let Some((start, end)) = self.0.next() {
-            Some(&self.0.string[start..end])
-        } else {
-            None
-        }
+        self.0.next().map(|(start, end)| &self.0.string[start..end])
     }
 }
 
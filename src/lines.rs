@@ -0,0 +1,207 @@
+//! Line-aware iteration: split text into lines, then segment and measure each line.
+//!
+//! A line terminator such as `"\r\n"` is a grapheme, so [print_position_data](crate::print_position_data)
+//! returns it as a print position, which means nothing to someone measuring how wide the text is.
+//! [lines] splits text at its line terminators instead, and each [Line] provides the print positions and width of
+//! its content, along with the style and hyperlink carried over from the escape sequences of the lines before it.
+//!
+//! ```rust
+//! use print_positions::lines::lines;
+//! use print_positions::style::{Color, Style};
+//!
+//! let content = "\u{1b}[32mgreen\r\nstill green\u{1b}[0m\nplain";
+//! let split: Vec<_> = lines(content).collect();
+//! assert_eq!(split.len(), 3);
+//! assert_eq!(split[1].as_str(), "still green\u{1b}[0m");
+//! assert_eq!(split[1].terminator(), "\n");
+//! assert_eq!(split[1].width(), 11);
+//! assert_eq!(split[1].style().foreground, Some(Color::Indexed(2)));  // set on the line before
+//! assert_eq!(split[2].style(), Style::default());
+//!
+//! assert_eq!(lines(content).max_width(), 11);
+//! ```
+
+use core::ops::Range;
+
+use crate::hyperlink::Hyperlink;
+use crate::position::PrintPositionInfo;
+use crate::rendition::Rendition;
+use crate::style::Style;
+use crate::{PrintPositions, Segmenter};
+
+#[cfg(test)]
+mod tests;
+
+/// Whether `grapheme` ends a line: LF, CR LF, CR, VT, FF, NEL (`U+0085`), LS (`U+2028`) or PS (`U+2029`).
+///
+/// These are the mandatory line breaks of [UAX#14](https://www.unicode.org/reports/tr14/).
+pub fn is_line_terminator(grapheme: &str) -> bool {
+    matches!(
+        grapheme,
+        "\n" | "\r\n" | "\r" | "\u{b}" | "\u{c}" | "\u{85}" | "\u{2028}" | "\u{2029}"
+    )
+}
+
+/// Factory method to provide a new [Lines] iterator.
+///
+/// Like [str::lines], a terminator at the very end of `s` doesn't start another (empty) line.
+#[inline]
+pub fn lines(s: &str) -> Lines<'_> {
+    Lines::new(s, Segmenter::new())
+}
+
+/// This iterator returns the lines of a string as [Line]s.
+///
+/// Line terminators found inside escape sequences don't end a line.  Escape sequences just before a terminator
+/// belong to the line they end, those just after it (such as a reset) belong to the next line, or, after the
+/// last terminator of the string, to the line before as its [trailing escapes](Line::trailing_escapes).
+#[derive(Clone)]
+pub struct Lines<'a> {
+    string: &'a str,
+    iter: PrintPositionInfo<'a>,
+    // offset where the next line starts
+    offset: usize,
+    // style and hyperlink in effect at `offset`
+    rendition: Rendition<'a>,
+    segmenter: Segmenter<'a>,
+}
+
+impl<'a> Lines<'a> {
+    pub(crate) fn new(s: &'a str, segmenter: Segmenter<'a>) -> Self {
+        Lines {
+            string: s,
+            iter: segmenter.print_position_info(s),
+            offset: 0,
+            rendition: Rendition::default(),
+            segmenter,
+        }
+    }
+
+    /// View the underlying data (the part yet to be iterated) as a slice of the original string.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        &self.string[self.offset..]
+    }
+
+    /// Width of the widest of the remaining lines, 0 if there are none.
+    pub fn max_width(self) -> usize {
+        self.map(|line| line.width()).max().unwrap_or(0)
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.string.len() {
+            return None;
+        }
+        let start = self.offset;
+        let rendition = self.rendition;
+        // escapes between the last terminator and the next print position
+        let iter_offset = self.string.len() - self.iter.as_str().len();
+        self.rendition.apply(&self.string[start..iter_offset]);
+
+        let mut end = self.string.len();
+        let mut terminator = "";
+        let mut trailing_escapes = "";
+        for p in self.iter.by_ref() {
+            if is_line_terminator(p.grapheme()) {
+                self.rendition.apply(p.prefix_escapes());
+                end = p.range().start + p.prefix_escapes().len();
+                terminator = p.grapheme();
+                // escapes after the last terminator of the string are part of its print position,
+                // there's no next line for them to belong to
+                if p.range().end == self.string.len() {
+                    trailing_escapes = p.suffix_escapes();
+                }
+                break;
+            }
+            self.rendition.apply(p.as_str());
+        }
+        self.offset = end + terminator.len() + trailing_escapes.len();
+
+        Some(Line {
+            string: self.string,
+            range: start..end,
+            terminator,
+            trailing_escapes,
+            rendition,
+            segmenter: self.segmenter,
+        })
+    }
+}
+
+/// One line of a source string, without its terminator.  Returned by iterator [Lines].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line<'a> {
+    // the source string the line was found in
+    string: &'a str,
+    range: Range<usize>,
+    terminator: &'a str,
+    trailing_escapes: &'a str,
+    // style and hyperlink in effect at the start of the line
+    rendition: Rendition<'a>,
+    segmenter: Segmenter<'a>,
+}
+
+impl<'a> Line<'a> {
+    /// The content of the line, including its escape sequences but not its terminator.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        &self.string[self.range.clone()]
+    }
+
+    /// Start and end + 1 offsets of the line's content in the source string.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// The line terminator, empty for a last line which has none.  See [is_line_terminator].
+    #[inline]
+    pub fn terminator(&self) -> &'a str {
+        self.terminator
+    }
+
+    /// Escape sequences after the terminator, if it's the last one of the string.  Empty for any other line.
+    /// Like [PrintPosition::suffix_escapes](crate::PrintPosition::suffix_escapes), this includes any zero width
+    /// graphemes the [Segmenter] attaches there.
+    #[inline]
+    pub fn trailing_escapes(&self) -> &'a str {
+        self.trailing_escapes
+    }
+
+    /// The style in effect at the start of the line, selected by escape sequences in the lines before it.
+    #[inline]
+    pub fn style(&self) -> Style {
+        self.rendition.style
+    }
+
+    /// The OSC 8 hyperlink open at the start of the line, if any.
+    #[inline]
+    pub fn hyperlink(&self) -> Option<Hyperlink<'a>> {
+        self.rendition.link
+    }
+
+    /// Iterate through the print positions of the line's content, offsets are relative to [Line::as_str].
+    #[inline]
+    pub fn print_positions(&self) -> PrintPositions<'a> {
+        self.segmenter.print_positions(self.as_str())
+    }
+
+    /// Iterate through the print positions of the line's content as [PrintPosition](crate::PrintPosition)s.
+    ///
+    /// Note that [PrintPosition::hyperlink](crate::PrintPosition::hyperlink) only reflects the escape sequences
+    /// within the line, see [Line::hyperlink] for the link carried over from earlier lines.
+    #[inline]
+    pub fn print_position_info(&self) -> PrintPositionInfo<'a> {
+        self.segmenter.print_position_info(self.as_str())
+    }
+
+    /// Number of columns the line's content occupies on the screen.
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.segmenter.display_width(self.as_str())
+    }
+}
@@ -0,0 +1,122 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::style::Color;
+
+fn split(s: &str) -> Vec<(&str, &str)> {
+    lines(s).map(|l| (l.as_str(), l.terminator())).collect()
+}
+
+#[test]
+fn terminators() -> Result<()> {
+    let content = "a\nb\r\nc\rd\u{b}e\u{c}f\u{85}g\u{2028}h\u{2029}i";
+    assert_eq!(
+        split(content),
+        vec![
+            ("a", "\n"),
+            ("b", "\r\n"),
+            ("c", "\r"),
+            ("d", "\u{b}"),
+            ("e", "\u{c}"),
+            ("f", "\u{85}"),
+            ("g", "\u{2028}"),
+            ("h", "\u{2029}"),
+            ("i", ""),
+        ]
+    );
+    for line in lines(content) {
+        assert_eq!(&content[line.range()], line.as_str());
+        assert_eq!(line.width(), 1);
+        assert_eq!(line.print_positions().count(), 1);
+    }
+
+    assert_eq!(split(""), vec![]);
+    assert_eq!(split("a\n"), vec![("a", "\n")]);
+    assert_eq!(split("\n\n"), vec![("", "\n"), ("", "\n")]);
+    assert_eq!(split("\n\ra"), vec![("", "\n"), ("", "\r"), ("a", "")]);
+    assert!(!is_line_terminator("\t"));
+    Ok(())
+}
+
+#[test]
+fn escapes_at_line_ends() -> Result<()> {
+    // escapes before a terminator belong to its line, a reset after it to the next
+    let content = "a\u{1b}[31m\nb\n\u{1b}[0m\u{1b}]0;title\u{7}\u{2028}c\nd\n\u{1b}[m";
+    assert_eq!(
+        split(content),
+        vec![
+            ("a\u{1b}[31m", "\n"),
+            ("b", "\n"),
+            ("\u{1b}[0m\u{1b}]0;title\u{7}", "\u{2028}"),
+            ("c", "\n"),
+            ("d", "\n"),
+        ]
+    );
+    let trailing: Vec<_> = lines(content).map(|l| l.trailing_escapes()).collect();
+    assert_eq!(trailing, vec!["", "", "", "", "\u{1b}[m"]);
+    let widths: Vec<_> = lines(content).map(|l| l.width()).collect();
+    assert_eq!(widths, vec![1, 1, 0, 1, 1]);
+
+    // trailing escapes after the last terminator don't make a line of their own
+    assert_eq!(split("a\n\u{1b}[0m"), vec![("a", "\n")]);
+    let line = lines("\r\n\u{1b}[0m\u{1b}[1m").next().unwrap();
+    assert_eq!(
+        (line.terminator(), line.trailing_escapes()),
+        ("\r\n", "\u{1b}[0m\u{1b}[1m")
+    );
+    assert!(is_line_terminator(line.terminator()));
+
+    // a newline inside an OSC doesn't end the line
+    assert_eq!(
+        split("a\u{1b}]0;x\ny\u{7}b\nc"),
+        vec![("a\u{1b}]0;x\ny\u{7}b", "\n"), ("c", "")]
+    );
+    Ok(())
+}
+
+#[test]
+fn carried_state() -> Result<()> {
+    let content = "\u{1b}[1mbold\n\u{1b}[31mred\u{1b}]8;;http://x.org\u{1b}\\link\nstill\u{1b}[0m\n\u{1b}[m\u{1b}]8;;\u{1b}\\end";
+    let lines: Vec<_> = lines(content).collect();
+    assert_eq!(lines.len(), 4);
+
+    assert_eq!(lines[0].style(), Style::default());
+    assert!(lines[1].style().bold);
+    assert_eq!(lines[1].style().foreground, None);
+    assert_eq!(lines[1].hyperlink(), None);
+    assert!(lines[2].style().bold);
+    assert_eq!(lines[2].style().foreground, Some(Color::Indexed(1)));
+    assert_eq!(lines[2].hyperlink().map(|l| l.uri()), Some("http://x.org"));
+    assert_eq!(lines[3].style(), Style::default());
+    assert!(lines[3].hyperlink().is_some(), "closed within the line");
+
+    // the line's own print positions don't know about earlier lines
+    assert!(lines[2]
+        .print_position_info()
+        .all(|p| p.hyperlink().is_none()));
+    Ok(())
+}
+
+#[test]
+fn widths() -> Result<()> {
+    let content = "\u{1b}[1mab\u{1b}[0m\r\n\u{4e2d}\u{6587}\u{4e2d}\n\u{1f600}";
+    assert_eq!(lines(content).max_width(), 6);
+    assert_eq!(lines("").max_width(), 0);
+
+    let mut iter = lines(content);
+    iter.next();
+    assert_eq!(iter.as_str(), "\u{4e2d}\u{6587}\u{4e2d}\n\u{1f600}");
+    assert_eq!(iter.max_width(), 6);
+
+    let segmenter = Segmenter::new().attach_zero_width(true);
+    let line = segmenter
+        .lines("\u{200b}x\u{200b}y\n")
+        .next()
+        .context("a line")?;
+    assert_eq!(line.print_positions().count(), 2);
+    assert_eq!(line.width(), 2);
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! Parallel segmentation of very large strings, built with feature `rayon`.
+//!
+//! The string is cut into chunks at print position boundaries from which segmenting the rest of the string
+//! on its own finds the same print positions, and the chunks are segmented in parallel.
+//! Such a boundary is the end of the first print position after a line feed which isn't inside an OSC sequence
+//! (like [next_boundary](crate::next_boundary), see module `boundary`), so the text needs line feeds to be cut:
+//! a string with none is segmented as a single chunk.
+//!
+//! ```rust
+//! use print_positions::parallel::{par_count, par_print_positions};
+//! use print_positions::print_positions;
+//! use rayon::prelude::*;
+//!
+//! let log = "\u{1b}[32mINFO\u{1b}[0m started\n".repeat(10_000);
+//! let positions: Vec<_> = par_print_positions(&log).collect();
+//! assert_eq!(positions, print_positions(&log).collect::<Vec<_>>());
+//! assert_eq!(par_count(&log), 13 * 10_000);
+//! ```
+
+use core::ops::Range;
+
+use rayon::prelude::*;
+
+use crate::boundary::last_osc_event;
+use crate::{print_position_data, print_positions};
+
+#[cfg(test)]
+mod tests;
+
+// chunks are at least this long, so each is worth a task of its own
+const MIN_CHUNK_LEN: usize = 64 * 1024;
+
+/// Parallel iterator over the print positions of `s`, returning the same start and end offsets as
+/// [print_positions](crate::print_positions), in the same order when collected.
+pub fn par_print_positions(s: &str) -> impl ParallelIterator<Item = (usize, usize)> + '_ {
+    resync_chunks(s, default_chunk_len(s))
+        .into_par_iter()
+        .flat_map_iter(move |chunk| {
+            print_positions(&s[chunk.clone()])
+                .map(move |(start, end)| (chunk.start + start, chunk.start + end))
+        })
+}
+
+/// Parallel iterator over the print positions of `s` as slices, the same as
+/// [print_position_data](crate::print_position_data), in the same order when collected.
+pub fn par_print_position_data(s: &str) -> impl ParallelIterator<Item = &str> + '_ {
+    resync_chunks(s, default_chunk_len(s))
+        .into_par_iter()
+        .flat_map_iter(move |chunk| print_position_data(&s[chunk]))
+}
+
+/// Number of print positions in `s`, counted in parallel.
+pub fn par_count(s: &str) -> usize {
+    resync_chunks(s, default_chunk_len(s))
+        .into_par_iter()
+        .map(|chunk| print_positions(&s[chunk]).count())
+        .sum()
+}
+
+/// Cut `s` into chunks of roughly `chunk_len` bytes (or more), each of which segments on its own
+/// into the same print positions as it does as part of `s`.
+///
+/// The chunks are contiguous, in order, and cover all of `s`.  For doing more with each chunk than
+/// the functions of this module do, such as measuring its width.
+pub fn resync_chunks(s: &str, chunk_len: usize) -> Vec<Range<usize>> {
+    let bytes = s.as_bytes();
+    let chunk_len = chunk_len.max(1);
+    let targets = s.len().saturating_sub(1) / chunk_len;
+
+    // For each target offset (a multiple of chunk_len): the last OSC event in the block before it,
+    // the first line feed after it, and the last OSC event between the two.
+    let found: Vec<_> = (1..=targets)
+        .into_par_iter()
+        .map(|k| {
+            let target = k * chunk_len;
+            let block = last_osc_event(bytes, target - chunk_len..target);
+            let next_target = (target + chunk_len).min(s.len());
+            let line = bytes[target..next_target]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map(|lf| {
+                    let after = target + lf + 1;
+                    (after, last_osc_event(bytes, target..after))
+                });
+            (block, line)
+        })
+        .collect();
+
+    // Whether an OSC sequence might be open is carried along from block to block, keeping the cuts where none is.
+    let mut anchors = vec![];
+    let mut osc_open = false;
+    for (block, line) in found {
+        osc_open = block.unwrap_or(osc_open);
+        if let Some((after, event)) = line {
+            if !event.unwrap_or(osc_open) {
+                anchors.push(after);
+            }
+        }
+    }
+
+    // The line feed might have ended an escape sequence rather than a print position,
+    // but the first print position after it ends where it would have anyway.
+    let cuts: Vec<_> = anchors
+        .into_par_iter()
+        .filter_map(|after| {
+            print_positions(&s[after..])
+                .next()
+                .map(|(_, end)| after + end)
+        })
+        .collect();
+
+    let mut chunks = vec![];
+    let mut start = 0;
+    for cut in cuts {
+        if cut > start && cut < s.len() {
+            chunks.push(start..cut);
+            start = cut;
+        }
+    }
+    chunks.push(start..s.len());
+    chunks
+}
+
+fn default_chunk_len(s: &str) -> usize {
+    (s.len() / (4 * rayon::current_num_threads())).max(MIN_CHUNK_LEN)
+}
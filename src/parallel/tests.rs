@@ -0,0 +1,63 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::fixtures::Random;
+
+// the chunks cover the string and segment the same on their own
+fn check(s: &str, chunk_len: usize) {
+    let chunks = resync_chunks(s, chunk_len);
+    assert_eq!(chunks.first().map(|c| c.start), Some(0));
+    assert_eq!(chunks.last().map(|c| c.end), Some(s.len()));
+    assert!(chunks.windows(2).all(|pair| pair[0].end == pair[1].start));
+
+    let chunked: Vec<_> = chunks
+        .iter()
+        .flat_map(|chunk| {
+            print_positions(&s[chunk.clone()])
+                .map(|(start, end)| (chunk.start + start, chunk.start + end))
+        })
+        .collect();
+    let sequential: Vec<_> = print_positions(s).collect();
+    assert_eq!(chunked, sequential, "{s:?} in chunks {chunks:?}");
+}
+
+#[test]
+fn same_as_sequential() -> Result<()> {
+    let mut random = Random(0x5eed);
+    for _ in 0..1000 {
+        let pieces = random.below(40);
+        let s = random.text(pieces);
+        for chunk_len in [1, 2, 5, 16] {
+            check(&s, chunk_len);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn cuts_after_line_feeds() -> Result<()> {
+    let s = "one\ntwo\n\u{1b}[0mthree\nfour";
+    assert_eq!(resync_chunks(s, 3), vec![0..5, 5..13, 13..19, 19..22]);
+
+    // nowhere to cut inside an open OSC sequence, or without line feeds
+    let s = "\u{1b}]0;title\nstill title\nmore\u{7}\nend";
+    assert_eq!(resync_chunks(s, 4), vec![0..29, 29..31]);
+    assert_eq!(resync_chunks("no line feeds", 2), vec![0..13]);
+    assert_eq!(resync_chunks("", 2), vec![0..0]);
+    Ok(())
+}
+
+#[test]
+fn parallel_iterators() -> Result<()> {
+    let s = "\u{1b}[1;31mERROR\u{1b}[0m disk \u{1f4be} full\n".repeat(20_000);
+    let positions: Vec<_> = par_print_positions(&s).collect();
+    assert_eq!(positions, print_positions(&s).collect::<Vec<_>>());
+    let data: Vec<_> = par_print_position_data(&s).collect();
+    assert_eq!(data, print_position_data(&s).collect::<Vec<_>>());
+    assert_eq!(par_count(&s), positions.len());
+    assert!(resync_chunks(&s, default_chunk_len(&s)).len() > 1);
+    Ok(())
+}
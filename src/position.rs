@@ -0,0 +1,194 @@
+//! A print position taken apart into its escape sequences and grapheme.
+
+use core::ops::Range;
+
+use crate::hyperlink::{self, Hyperlink};
+use crate::segmenter::Segmenter;
+use crate::{Parts, PrintPositions};
+
+/// One print position in a source string, with accessors for its component parts.
+///
+/// Returned by iterator [PrintPositionInfo].  It's a lightweight view of the source string (a reference and some offsets),
+/// so the parts are available without re-parsing the print position.
+///
+/// ```rust
+/// use print_positions::print_position_info;
+///
+/// let content = "a\u{1b}[31m\u{4e2d}\u{1b}[0m";
+/// let positions: Vec<_> = print_position_info(content).collect();
+/// assert_eq!(positions.len(), 2);
+///
+/// let p = positions[1];
+/// assert_eq!(p.as_str(), "\u{1b}[31m\u{4e2d}\u{1b}[0m");
+/// assert_eq!(p.range(), 1..13);
+/// assert_eq!(p.prefix_escapes(), "\u{1b}[31m");
+/// assert_eq!(p.grapheme(), "\u{4e2d}");
+/// assert_eq!(p.suffix_escapes(), "\u{1b}[0m");
+/// assert_eq!(p.width(), 2);       // CJK ideograph is double width
+/// assert!(!p.is_control());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrintPosition<'a> {
+    // the source string the print position was found in
+    string: &'a str,
+    parts: Parts,
+    hyperlink: Option<Hyperlink<'a>>,
+    segmenter: Segmenter<'a>,
+}
+
+impl<'a> PrintPosition<'a> {
+    /// The whole print position: escape sequences and grapheme.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        &self.string[self.parts.start..self.parts.end]
+    }
+
+    /// Start and end + 1 offsets of the print position in the source string,
+    /// as returned by [PrintPositions](crate::PrintPositions).
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.parts.start..self.parts.end
+    }
+
+    /// The grapheme cluster only, without any escape sequences.
+    ///
+    /// Empty if the print position consists only of escape sequences (see [has_grapheme](crate::has_grapheme)).
+    #[inline]
+    pub fn grapheme(&self) -> &'a str {
+        &self.string[self.parts.grapheme_start..self.parts.grapheme_end]
+    }
+
+    /// Escape sequences preceeding the grapheme, empty if there are none.
+    ///
+    /// If the [Segmenter] attaches zero width graphemes (see [Segmenter::attach_zero_width]), this also holds
+    /// the ones attached before the grapheme, in their place among the escape sequences.  Code which interprets
+    /// escape sequences, such as [Style::apply_escapes](crate::style::Style::apply_escapes), skips them.
+    #[inline]
+    pub fn prefix_escapes(&self) -> &'a str {
+        &self.string[self.parts.start..self.parts.grapheme_start]
+    }
+
+    /// Escape sequences following the grapheme, empty if there are none.
+    ///
+    /// These are reset sequences, or escape sequences at the end of the source string.
+    ///
+    /// If the [Segmenter] attaches zero width graphemes (see [Segmenter::attach_zero_width]), this also holds
+    /// the ones attached at the end of the source string, in their place among the escape sequences.  Code which
+    /// interprets escape sequences, such as [Style::apply_escapes](crate::style::Style::apply_escapes), skips them.
+    #[inline]
+    pub fn suffix_escapes(&self) -> &'a str {
+        &self.string[self.parts.grapheme_end..self.parts.end]
+    }
+
+    /// Whether the print position contains a grapheme, as opposed to just escape sequences.
+    #[inline]
+    pub fn has_grapheme(&self) -> bool {
+        self.parts.grapheme_end > self.parts.grapheme_start
+    }
+
+    /// Number of columns the print position occupies on the screen.
+    ///
+    /// Escape sequences and control characters take no space, East Asian wide characters and emoji take 2 columns.
+    /// Widths follow the [Segmenter] the print position came from, see module [width](crate::width).
+    #[inline]
+    pub fn width(&self) -> usize {
+        self.segmenter.grapheme_width(self.grapheme())
+    }
+
+    /// Whether the grapheme takes no space on the screen, although it isn't a control character.
+    /// See [Segmenter::is_zero_width].
+    #[inline]
+    pub fn is_zero_width(&self) -> bool {
+        self.segmenter.is_zero_width(self.grapheme())
+    }
+
+    /// The OSC 8 hyperlink the grapheme falls inside, if any.
+    ///
+    /// This is the link opened by the escape sequences before the grapheme, including this print position's
+    /// own prefix escapes, and not yet closed.  See module [hyperlink](crate::hyperlink).
+    #[inline]
+    pub fn hyperlink(&self) -> Option<Hyperlink<'a>> {
+        self.hyperlink
+    }
+
+    /// Whether the grapheme is a control character, such as `\t` or `\n` (or `\r\n`).
+    #[inline]
+    pub fn is_control(&self) -> bool {
+        self.grapheme().starts_with(char::is_control)
+    }
+}
+
+/// This iterator returns each print position found in a string as a [PrintPosition],
+/// which provides the grapheme, escape sequences and display width of the print position.
+///
+/// It segments the string exactly the same way as [PrintPositions] and [PrintPositionData](crate::PrintPositionData).
+///
+/// ```rust
+/// use print_positions::print_position_info;
+///
+/// let content = "\u{1b}[1mbold\u{1b}[0m and \u{1f600}";
+/// let graphemes: String = print_position_info(content).map(|p| p.grapheme()).collect();
+/// assert_eq!(graphemes, "bold and \u{1f600}");
+///
+/// let width: usize = print_position_info(content).map(|p| p.width()).sum();
+/// assert_eq!(width, 11);  // emoji is double width
+/// ```
+#[derive(Clone)]
+pub struct PrintPositionInfo<'a> {
+    iter: PrintPositions<'a>,
+    // hyperlink open at the end of the last print position returned
+    hyperlink: Option<Hyperlink<'a>>,
+    segmenter: Segmenter<'a>,
+}
+
+/// Factory method to provide a new [PrintPositionInfo] iterator.
+///
+#[inline]
+pub fn print_position_info(s: &str) -> PrintPositionInfo<'_> {
+    PrintPositionInfo::new(s, Segmenter::new())
+}
+
+impl<'a> PrintPositionInfo<'a> {
+    pub(crate) fn new(s: &'a str, segmenter: Segmenter<'a>) -> Self {
+        PrintPositionInfo {
+            iter: segmenter.print_positions(s),
+            hyperlink: None,
+            segmenter,
+        }
+    }
+
+    /// View the underlying data (the part yet to be iterated) as a slice of the original string.
+    ///
+    /// ```rust
+    /// # use print_positions::print_position_info;
+    /// let mut iter = print_position_info("abc");
+    /// iter.next();
+    /// assert_eq!(iter.as_str(), "bc");
+    /// ```
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.iter.as_str()
+    }
+}
+
+impl<'a> Iterator for PrintPositionInfo<'a> {
+    type Item = PrintPosition<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let parts = self.iter.next_parts()?;
+        let string = self.iter.string;
+        hyperlink::apply(
+            &string[parts.start..parts.grapheme_start],
+            &mut self.hyperlink,
+        );
+        let retval = PrintPosition {
+            string,
+            parts,
+            hyperlink: self.hyperlink,
+            segmenter: self.segmenter,
+        };
+        hyperlink::apply(&string[parts.grapheme_end..parts.end], &mut self.hyperlink);
+        Some(retval)
+    }
+}
@@ -0,0 +1,90 @@
+//! Graphic rendition state: the style and hyperlink in effect at some point in a string.
+
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use crate::escape::{escapes, Escape};
+#[cfg(feature = "alloc")]
+use crate::hyperlink;
+use crate::hyperlink::Hyperlink;
+use crate::style::Style;
+
+/// Style and hyperlink selected by the escape sequences seen so far.
+///
+/// Text cut out of the middle of a string renders the same on its own if it's preceeded by [Rendition::write_open]
+/// for the state at the cut and followed by [Rendition::write_close] for the state at its end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Rendition<'a> {
+    pub(crate) style: Style,
+    pub(crate) link: Option<Hyperlink<'a>>,
+}
+
+impl<'a> Rendition<'a> {
+    /// Update the state for the escape sequences in `escapes_str`.
+    pub(crate) fn apply(&mut self, escapes_str: &'a str) {
+        for escape in escapes(escapes_str) {
+            match escape {
+                Escape::Csi {
+                    params,
+                    final_byte: b'm',
+                } => self.style.apply_sgr(params),
+                Escape::Osc(data) => {
+                    if let Some(link) = Hyperlink::parse(data) {
+                        self.link = link;
+                    }
+                }
+                Escape::Simple(b'c') => *self = Rendition::default(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Write the escape sequences which establish this state, starting from the initial state.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn write_open(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        if !self.style.is_default() {
+            write!(out, "{}", self.style.sgr())?;
+        }
+        if let Some(link) = self.link {
+            write!(out, "{}", link.open())?;
+        }
+        Ok(())
+    }
+
+    /// Write the escape sequences which return from this state to the initial state.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn write_close(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        if self.link.is_some() {
+            out.write_str(hyperlink::CLOSE)?;
+        }
+        if !self.style.is_default() {
+            out.write_str("\x1b[0m")?;
+        }
+        Ok(())
+    }
+
+    /// Write the escape sequences which change this state to `to`, nothing if they're the same.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn write_transition(
+        &self,
+        to: &Rendition,
+        out: &mut impl fmt::Write,
+    ) -> fmt::Result {
+        if self.link != to.link {
+            if self.link.is_some() {
+                out.write_str(hyperlink::CLOSE)?;
+            }
+            if let Some(link) = to.link {
+                write!(out, "{}", link.open())?;
+            }
+        }
+        if self.style != to.style {
+            if to.style.is_default() {
+                out.write_str("\x1b[0m")?;
+            } else {
+                write!(out, "{}", to.style.sgr())?;
+            }
+        }
+        Ok(())
+    }
+}
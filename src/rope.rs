@@ -0,0 +1,577 @@
+//! A print position buffer for large documents with frequent edits.
+//!
+//! [Rope] stores text in a balanced tree of small chunks, each caching its number of print positions,
+//! display width and effect on the style.  Finding the Nth print position, its style or its byte offset, and
+//! editing in the middle of the document take time proportional to the log of the document size rather than to
+//! the size itself.
+//!
+//! ```rust
+//! use print_positions::rope::Rope;
+//!
+//! let mut rope = Rope::from("\u{1b}[1mbold\u{1b}[0m and plain\n".repeat(10_000).as_str());
+//! assert_eq!(rope.len(), 150_000);
+//! rope.replace(15_005..15_008, "or");     // "and" in the second line
+//! assert_eq!(rope.get(15_005), Some("o"));
+//! assert!(rope.style_at(15_001).bold);
+//! assert_eq!(rope.width(), 139_999);      // newlines take no space
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::style::{Color, Style};
+use crate::{display_width, print_position_data, print_positions, EscapeState};
+
+#[cfg(test)]
+mod tests;
+
+// Chunks are cut to hold at most this many bytes, unless a single print position is bigger.
+const LEAF_SIZE: usize = 1024;
+
+/// Text with escape sequences, indexed by print position, for documents too big to re-segment after each edit.
+///
+/// The text is stored in chunks which start and end on print position boundaries, so each chunk segments
+/// on its own exactly as it does as part of the whole text, and segmentation follows the same rules as
+/// [PrintPositions](crate::PrintPositions).  An edit re-segments the text around it until segmentation of the
+/// edited text falls back into step with the unchanged text after it, usually within the same chunk.
+/// (An edit which changes how the rest of the document is segmented, like inserting the start of an
+/// unterminated OSC sequence, is proportional to the rest of the document.)
+///
+/// Edits work on the text as is: unlike [StyledString](crate::styled::StyledString), they don't add escape sequences
+/// to keep styles intact.  Widths follow the default [WidthProfile](crate::width::WidthProfile).
+#[derive(Clone, Default)]
+pub struct Rope {
+    root: Tree,
+    // state of the generator for node priorities
+    seed: u64,
+}
+
+impl Rope {
+    /// An empty rope.
+    pub fn new() -> Self {
+        Rope::default()
+    }
+
+    /// Number of print positions.
+    #[inline]
+    pub fn len(&self) -> usize {
+        summary(&self.root).positions
+    }
+
+    /// Whether there are no print positions (and so no text).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Length of the text in bytes.
+    #[inline]
+    pub fn len_bytes(&self) -> usize {
+        summary(&self.root).bytes
+    }
+
+    /// Number of columns the text occupies on the screen.  See [display_width].
+    #[inline]
+    pub fn width(&self) -> usize {
+        summary(&self.root).width
+    }
+
+    /// Print position `index`, or `None` if there are not that many.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        let (leaf, start) = self.locate(index)?;
+        print_position_data(&leaf.leaf).nth(index - start.positions)
+    }
+
+    /// Byte offset of print position `index` in the text, or the length of the text if `index` is the number of print positions.
+    ///
+    /// Panics if `index` is greater than the number of print positions.
+    pub fn byte_offset(&self, index: usize) -> usize {
+        self.check(&(index..index));
+        match self.locate(index) {
+            Some((leaf, start)) => start.bytes + leaf_offset(&leaf.leaf, index - start.positions),
+            None => self.len_bytes(),
+        }
+    }
+
+    /// The style in effect at print position `index`, before the print position's own escape sequences.
+    ///
+    /// Panics if `index` is greater than the number of print positions.
+    pub fn style_at(&self, index: usize) -> Style {
+        self.check(&(index..index));
+        match self.locate(index) {
+            Some((leaf, start)) => {
+                let mut style = start.style.apply(Style::default());
+                let offset = leaf_offset(&leaf.leaf, index - start.positions);
+                style.apply_escapes(&leaf.leaf[..offset]);
+                style
+            }
+            None => summary(&self.root).style.apply(Style::default()),
+        }
+    }
+
+    /// Iterate through the print positions, like [print_position_data].
+    pub fn print_position_data(&self) -> impl Iterator<Item = &str> + '_ {
+        self.chunks().flat_map(print_position_data)
+    }
+
+    /// Iterate through the chunks of text the rope is stored in.  Each contains whole print positions.
+    pub fn chunks(&self) -> Chunks<'_> {
+        let mut chunks = Chunks { stack: vec![] };
+        chunks.push_left(self.root.as_deref());
+        chunks
+    }
+
+    /// Append `s`.
+    pub fn push(&mut self, s: &str) {
+        self.replace(self.len()..self.len(), s);
+    }
+
+    /// Insert `s` before print position `index`.
+    ///
+    /// Panics if `index` is greater than the number of print positions.
+    pub fn insert(&mut self, index: usize, s: &str) {
+        self.replace(index..index, s);
+    }
+
+    /// Remove print positions `range.start` up to (but not including) `range.end`.
+    ///
+    /// Panics if the range extends past the end of the text.
+    pub fn remove(&mut self, range: Range<usize>) {
+        self.replace(range, "");
+    }
+
+    /// Replace print positions `range.start` up to (but not including) `range.end` with `s`.
+    ///
+    /// Panics if the range extends past the end of the text.
+    pub fn replace(&mut self, range: Range<usize>, s: &str) {
+        self.check(&range);
+        // The edit can change where the print position before it ends (e.g. a combining mark joins its grapheme),
+        // but nothing before that.
+        let first = range.start.saturating_sub(1);
+        let root = self.root.take();
+        let (left, rest) = self.split_at(root, first);
+        let (middle, mut right) = self.split_at(rest, range.end - first);
+        let (kept, _removed) = self.split_at(middle, range.start - first);
+        // start with the whole chunk, so edits don't fragment the chunks
+        let (left, last) = pop_last(left);
+
+        let mut working = last.map(|n| n.leaf).unwrap_or_default();
+        for chunk in (Chunks { stack: vec![] }).start(kept.as_deref()) {
+            working.push_str(chunk);
+        }
+        working.push_str(s);
+        let edit_end = working.len();
+
+        // Re-segment, pulling in following chunks, until the new print positions line up with the old ones
+        // somewhere after the edit. Boundaries at the very end of `working` don't count: they may move once more text follows.
+        let mut old_starts = vec![];
+        let mut resync = Resync::default();
+        while right.is_some() && !resync.lined_up(&working, edit_end, &old_starts) {
+            let (next, rest) = pop_first(right);
+            right = rest;
+            let next = next.expect("tree not empty");
+            old_starts.extend(print_positions(&next.leaf).map(|(start, _)| working.len() + start));
+            working.push_str(&next.leaf);
+        }
+
+        let mut middle = None;
+        let mut chunk_start = 0;
+        for (start, end) in print_positions(&working) {
+            if end - chunk_start > LEAF_SIZE && start > chunk_start {
+                let node = self.node(working[chunk_start..start].to_string());
+                middle = merge(middle, node);
+                chunk_start = start;
+            }
+        }
+        if chunk_start < working.len() {
+            let node = self.node(working[chunk_start..].to_string());
+            middle = merge(middle, node);
+        }
+
+        self.root = merge(merge(left, middle), right);
+    }
+
+    // the chunk containing print position `index` and the summary of everything before it
+    fn locate(&self, mut index: usize) -> Option<(&Node, Summary)> {
+        let mut before = Summary::default();
+        let mut tree = self.root.as_deref();
+        while let Some(node) = tree {
+            let left = summary(&node.left);
+            if index < left.positions {
+                tree = node.left.as_deref();
+            } else if index < left.positions + node.leaf_summary.positions {
+                return Some((node, before.then(left)));
+            } else {
+                index -= left.positions + node.leaf_summary.positions;
+                before = before.then(left).then(node.leaf_summary);
+                tree = node.right.as_deref();
+            }
+        }
+        None
+    }
+
+    fn check(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "print position range {range:?} out of bounds for length {}",
+            self.len()
+        );
+    }
+
+    // a node for chunk `leaf`, with the next priority
+    fn node(&mut self, leaf: String) -> Tree {
+        // xorshift64*
+        let mut x = self.seed ^ 0x2545_f491_4f6c_dd1d;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.seed = x;
+        let leaf_summary = Summary::of(&leaf);
+        Some(Box::new(Node {
+            leaf,
+            priority: x.wrapping_mul(0x2545_f491_4f6c_dd1d),
+            leaf_summary,
+            summary: leaf_summary,
+            left: None,
+            right: None,
+        }))
+    }
+
+    // split `tree` into the first `index` print positions and the rest, cutting a chunk if need be.
+    fn split_at(&mut self, tree: Tree, index: usize) -> (Tree, Tree) {
+        let (left, right) = split(tree, index);
+        let before = summary(&left).positions;
+        if before == index {
+            return (left, right);
+        }
+        let (chunk, rest) = pop_first(right);
+        let mut chunk = chunk.expect("index within tree");
+        let second = chunk
+            .leaf
+            .split_off(leaf_offset(&chunk.leaf, index - before));
+        let first = self.node(chunk.leaf);
+        let second = self.node(second);
+        (merge(left, first), merge(second, rest))
+    }
+}
+
+// Where re-segmenting text which grows at the end can pick up again.
+//
+// Appending text can only change the last print position found so far, so each pass segments from its start.
+// While that print position ends in escape sequences (e.g. an unterminated OSC which swallows whatever follows),
+// appended text is only scanned, carrying on the escape state, until something other than an escape sequence
+// turns up.  So pulling in all the text after an edit costs time proportional to that text, not to its square.
+#[derive(Default)]
+struct Resync {
+    // start of the last print position
+    resume: usize,
+    // start of the last grapheme of the escape sequences ending the text, and the state before it
+    tail: Option<(usize, EscapeState)>,
+}
+
+impl Resync {
+    // whether `text` has a print position starting after `edit_end`, but not at its very end, which starts at
+    // one of `old_starts` too
+    fn lined_up(&mut self, text: &str, edit_end: usize, old_starts: &[usize]) -> bool {
+        if let Some((from, state)) = self.tail {
+            if let Some((offset, state)) = escape_tail(&text[from..], state) {
+                // still escape sequences, the last print position just got longer
+                self.tail = Some((from + offset, state));
+                return false;
+            }
+        }
+        let mut positions = print_positions(&text[self.resume..]);
+        let mut last = None;
+        while let Some(parts) = positions.next_parts() {
+            let start = self.resume + parts.start;
+            if start >= edit_end && start < text.len() && old_starts.binary_search(&start).is_ok() {
+                return true;
+            }
+            last = Some(parts);
+        }
+        if let Some(parts) = last {
+            // the escape sequences after the grapheme, or all of an escape-only print position
+            let escapes = if parts.grapheme_start < parts.grapheme_end {
+                parts.grapheme_end
+            } else {
+                parts.start
+            };
+            let from = self.resume + escapes;
+            self.tail = escape_tail(&text[from..], EscapeState::Normal)
+                .map(|(offset, state)| (from + offset, state));
+            self.resume += parts.start;
+        }
+        false
+    }
+}
+
+// If `s` is all escape sequences, continuing from `state`: the offset of its last grapheme and the state before it.
+fn escape_tail(s: &str, mut state: EscapeState) -> Option<(usize, EscapeState)> {
+    let mut last = (0, state);
+    for (offset, grapheme) in s.grapheme_indices(true) {
+        last = (offset, state);
+        state = state.advance(grapheme.as_bytes()[0])?;
+    }
+    Some(last)
+}
+
+impl From<&str> for Rope {
+    fn from(s: &str) -> Self {
+        let mut rope = Rope::new();
+        rope.push(s);
+        rope
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl fmt::Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.chunks()).finish()
+    }
+}
+
+/// This iterator returns the chunks of text a [Rope] is stored in, in order.
+pub struct Chunks<'a> {
+    stack: Vec<&'a Node>,
+}
+
+impl<'a> Chunks<'a> {
+    fn start(mut self, tree: Option<&'a Node>) -> Self {
+        self.push_left(tree);
+        self
+    }
+
+    fn push_left(&mut self, mut tree: Option<&'a Node>) {
+        while let Some(node) = tree {
+            self.stack.push(node);
+            tree = node.left.as_deref();
+        }
+    }
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.stack.pop()?;
+        self.push_left(node.right.as_deref());
+        Some(&node.leaf)
+    }
+}
+
+type Tree = Option<Box<Node>>;
+
+// A treap (a binary tree kept balanced by random priorities) of chunks, in order.
+#[derive(Clone)]
+struct Node {
+    leaf: String,
+    priority: u64,
+    leaf_summary: Summary,
+    // of the whole subtree
+    summary: Summary,
+    left: Tree,
+    right: Tree,
+}
+
+impl Node {
+    fn update(&mut self) {
+        self.summary = summary(&self.left)
+            .then(self.leaf_summary)
+            .then(summary(&self.right));
+    }
+}
+
+fn summary(tree: &Tree) -> Summary {
+    tree.as_ref().map(|node| node.summary).unwrap_or_default()
+}
+
+// byte offset of print position `index` in `leaf`
+fn leaf_offset(leaf: &str, index: usize) -> usize {
+    print_positions(leaf)
+        .nth(index)
+        .map(|(start, _)| start)
+        .unwrap_or(leaf.len())
+}
+
+fn merge(left: Tree, right: Tree) -> Tree {
+    match (left, right) {
+        (None, tree) | (tree, None) => tree,
+        (Some(mut l), Some(mut r)) => {
+            if l.priority > r.priority {
+                l.right = merge(l.right.take(), Some(r));
+                l.update();
+                Some(l)
+            } else {
+                r.left = merge(Some(l), r.left.take());
+                r.update();
+                Some(r)
+            }
+        }
+    }
+}
+
+// split `tree` into the chunks which end at or before print position `index`, and the rest
+fn split(tree: Tree, index: usize) -> (Tree, Tree) {
+    let Some(mut node) = tree else {
+        return (None, None);
+    };
+    let end = summary(&node.left).positions + node.leaf_summary.positions;
+    if end <= index {
+        let (l, r) = split(node.right.take(), index - end);
+        node.right = l;
+        node.update();
+        (Some(node), r)
+    } else {
+        let (l, r) = split(node.left.take(), index);
+        node.left = r;
+        node.update();
+        (l, Some(node))
+    }
+}
+
+// the first chunk, detached, and the rest of the tree
+fn pop_first(tree: Tree) -> (Option<Box<Node>>, Tree) {
+    let Some(mut node) = tree else {
+        return (None, None);
+    };
+    if node.left.is_none() {
+        let rest = node.right.take();
+        return (Some(node), rest);
+    }
+    let (first, rest) = pop_first(node.left.take());
+    node.left = rest;
+    node.update();
+    (first, Some(node))
+}
+
+// the rest of the tree, and the last chunk, detached
+fn pop_last(tree: Tree) -> (Tree, Option<Box<Node>>) {
+    let Some(mut node) = tree else {
+        return (None, None);
+    };
+    if node.right.is_none() {
+        let rest = node.left.take();
+        return (rest, Some(node));
+    }
+    let (rest, last) = pop_last(node.right.take());
+    node.right = rest;
+    node.update();
+    (Some(node), last)
+}
+
+/// What a stretch of text amounts to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Summary {
+    bytes: usize,
+    positions: usize,
+    width: usize,
+    style: StyleDelta,
+}
+
+impl Summary {
+    fn of(text: &str) -> Self {
+        Summary {
+            bytes: text.len(),
+            positions: print_positions(text).count(),
+            width: display_width(text),
+            style: StyleDelta::of(text),
+        }
+    }
+
+    // this text followed by `next`
+    fn then(self, next: Summary) -> Summary {
+        Summary {
+            bytes: self.bytes + next.bytes,
+            positions: self.positions + next.positions,
+            width: self.width + next.width,
+            style: self.style.then(next.style),
+        }
+    }
+}
+
+/// The change to the style made by the escape sequences in a stretch of text.
+///
+/// SGR parameters (and full reset) each set some fields of the style regardless of their previous values,
+/// so the change is fully described by what it makes of two styles which differ in every field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct StyleDelta {
+    // the change applied to the default style
+    from_default: Style,
+    // the change applied to PROBE
+    from_probe: Style,
+}
+
+const PROBE: Style = Style {
+    foreground: Some(Color::Indexed(0)),
+    background: Some(Color::Indexed(0)),
+    bold: true,
+    dim: true,
+    italic: true,
+    underline: true,
+    blink: true,
+    inverse: true,
+    hidden: true,
+    strikethrough: true,
+};
+
+impl Default for StyleDelta {
+    // no change
+    fn default() -> Self {
+        StyleDelta {
+            from_default: Style::default(),
+            from_probe: PROBE,
+        }
+    }
+}
+
+impl StyleDelta {
+    fn of(text: &str) -> Self {
+        let mut delta = StyleDelta::default();
+        delta.from_default.apply_escapes(text);
+        delta.from_probe.apply_escapes(text);
+        delta
+    }
+
+    // this change followed by `next`
+    fn then(self, next: StyleDelta) -> StyleDelta {
+        StyleDelta {
+            from_default: next.apply(self.from_default),
+            from_probe: next.apply(self.from_probe),
+        }
+    }
+
+    fn apply(self, style: Style) -> Style {
+        // a field which came out the same from both was set by the change, otherwise it was left alone.
+        fn pick<T: PartialEq>(from_default: T, from_probe: T, old: T) -> T {
+            if from_default == from_probe {
+                from_default
+            } else {
+                old
+            }
+        }
+        let (d, p) = (self.from_default, self.from_probe);
+        Style {
+            foreground: pick(d.foreground, p.foreground, style.foreground),
+            background: pick(d.background, p.background, style.background),
+            bold: pick(d.bold, p.bold, style.bold),
+            dim: pick(d.dim, p.dim, style.dim),
+            italic: pick(d.italic, p.italic, style.italic),
+            underline: pick(d.underline, p.underline, style.underline),
+            blink: pick(d.blink, p.blink, style.blink),
+            inverse: pick(d.inverse, p.inverse, style.inverse),
+            hidden: pick(d.hidden, p.hidden, style.hidden),
+            strikethrough: pick(d.strikethrough, p.strikethrough, style.strikethrough),
+        }
+    }
+}
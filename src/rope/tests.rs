@@ -0,0 +1,145 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::fixtures::Random;
+
+// compare everything the rope knows with the same computed from scratch
+fn check(rope: &Rope, expected: &str) {
+    assert_eq!(rope.to_string(), expected);
+    let positions: Vec<_> = print_positions(expected).collect();
+    assert_eq!(rope.len(), positions.len(), "{expected:?}");
+    assert_eq!(rope.is_empty(), expected.is_empty());
+    assert_eq!(rope.len_bytes(), expected.len());
+    assert_eq!(rope.width(), display_width(expected), "{expected:?}");
+
+    // chunks segment the same on their own
+    let data: Vec<_> = rope.print_position_data().collect();
+    let expected_data: Vec<_> = print_position_data(expected).collect();
+    assert_eq!(data, expected_data);
+    assert!(rope.chunks().all(|chunk| !chunk.is_empty()));
+
+    // style_at() from scratch for each print position would take too long with chunks of full size
+    let step = positions.len() / 100 + 1;
+    let mut style = Style::default();
+    let mut styled_to = 0;
+    for (i, &(start, end)) in positions.iter().enumerate().step_by(step) {
+        assert_eq!(rope.get(i), Some(&expected[start..end]));
+        assert_eq!(rope.byte_offset(i), start);
+        style.apply_escapes(&expected[styled_to..start]);
+        styled_to = start;
+        assert_eq!(rope.style_at(i), style, "style at {i} of {expected:?}");
+    }
+    let mut style = Style::default();
+    style.apply_escapes(expected);
+    assert_eq!(rope.style_at(rope.len()), style);
+    assert_eq!(rope.byte_offset(rope.len()), expected.len());
+    assert_eq!(rope.get(rope.len()), None);
+}
+
+#[test]
+fn build() -> Result<()> {
+    check(&Rope::new(), "");
+    check(&Rope::from(""), "");
+    check(&Rope::from("\u{1b}[31m"), "\u{1b}[31m");
+    let mut random = Random(12345);
+    for pieces in [1, 5, 50, 500, 5000] {
+        let text = random.text(pieces);
+        check(&Rope::from(text.as_str()), &text);
+    }
+    Ok(())
+}
+
+#[test]
+fn random_edits() -> Result<()> {
+    let mut random = Random(0x5eed);
+    let mut expected = random.text(1000);
+    let mut rope = Rope::from(expected.as_str());
+    for _ in 0..1000 {
+        let len = rope.len();
+        let start = random.below(len + 1);
+        let end = start + random.below((len - start).min(8) + 1);
+        let pieces = random.below(4);
+        let insert = random.text(pieces);
+
+        let positions: Vec<_> = print_positions(&expected).collect();
+        let offset = |i: usize| positions.get(i).map(|p| p.0).unwrap_or(expected.len());
+        let (start_byte, end_byte) = (offset(start), offset(end));
+        expected.replace_range(start_byte..end_byte, &insert);
+        rope.replace(start..end, &insert);
+        check(&rope, &expected);
+    }
+    Ok(())
+}
+
+#[test]
+fn seam_resegmentation() -> Result<()> {
+    let text = "abcdefghijklmnopqrstuvwxyz".repeat(100);
+    let cases = [
+        (3, "\u{301}"),   // joins the grapheme before
+        (3, "\u{1b}[0m"), // attaches to the print position before
+        (3, "\u{1b}]0;"), // swallows the rest of the text
+        (0, "\u{1b}["),   // swallows the following letter
+    ];
+    for (index, insert) in cases {
+        let mut rope = Rope::from(text.as_str());
+        assert!(rope.chunks().count() > 1);
+        rope.insert(index, insert);
+        let mut expected = text.clone();
+        expected.insert_str(index, insert);
+        check(&rope, &expected);
+    }
+
+    let mut rope = Rope::from(text.as_str());
+    rope.remove(1..2599);
+    check(&rope, "az");
+    rope.push("\u{1b}[1m");
+    rope.push("b");
+    check(&rope, "az\u{1b}[1mb");
+    rope.remove(0..rope.len());
+    check(&rope, "");
+    Ok(())
+}
+
+#[test]
+fn unterminated_osc_across_chunks() -> Result<()> {
+    let text = "\u{1b}[1mbold\u{1b}[0m and plain\n".repeat(5000);
+    for (insert, len) in [
+        ("\u{1b}]0;", 3),                // swallows the rest of the text
+        ("\u{1b}]0;title\u{7}", 75_000), // ends before the next print position
+    ] {
+        let mut rope = Rope::from(text.as_str());
+        assert!(rope.chunks().count() > 100);
+        let mut expected = text.clone();
+        expected.insert_str(rope.byte_offset(3), insert);
+        rope.insert(3, insert);
+        check(&rope, &expected);
+        assert_eq!(rope.len(), len);
+    }
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn out_of_bounds() {
+    Rope::from("ab").insert(3, "c");
+}
+
+#[test]
+fn style_delta() -> Result<()> {
+    let first = StyleDelta::of("\u{1b}[1;31m");
+    let second = StyleDelta::of("\u{1b}[22;44m");
+    let start = Style {
+        italic: true,
+        bold: true,
+        ..Style::default()
+    };
+    let mut expected = start;
+    expected.apply_escapes("\u{1b}[1;31m\u{1b}[22;44m");
+    assert_eq!(first.then(second).apply(start), expected);
+    assert_eq!(StyleDelta::default().apply(start), start);
+    assert_eq!(StyleDelta::of("\u{1b}c").apply(start), Style::default());
+    Ok(())
+}
@@ -0,0 +1,33 @@
+//! Fast scanning for the bytes which need the grapheme segmenter: ESC and non-ASCII.
+
+#[cfg(test)]
+mod tests;
+
+const LANES: usize = 8;
+const ONES: u64 = u64::from_ne_bytes([0x01; LANES]);
+const HIGH_BITS: u64 = u64::from_ne_bytes([0x80; LANES]);
+const ESCAPES: u64 = u64::from_ne_bytes([0x1b; LANES]);
+
+/// Length of the run of ASCII bytes other than ESC at the start of `bytes`.
+///
+/// Checks 8 bytes at a time, as a `u64` (SWAR: SIMD within a register).
+pub(crate) fn plain_ascii_len(bytes: &[u8]) -> usize {
+    let mut words = bytes.chunks_exact(LANES);
+    let mut len = 0;
+    for word in words.by_ref() {
+        let word = u64::from_le_bytes(word.try_into().expect("chunk of LANES bytes"));
+        // high bit of each non-ASCII byte, and of each zero byte of `word ^ ESCAPES` (each ESC).
+        // The zero byte test can also flag a byte just above a true zero byte, but never the lowest flagged byte.
+        let escapes = word ^ ESCAPES;
+        let found = (word | (escapes.wrapping_sub(ONES) & !escapes)) & HIGH_BITS;
+        if found != 0 {
+            return len + found.trailing_zeros() as usize / 8;
+        }
+        len += LANES;
+    }
+    len + words
+        .remainder()
+        .iter()
+        .take_while(|&&b| b.is_ascii() && b != 0x1b)
+        .count()
+}
@@ -0,0 +1,32 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[test]
+fn finds_first_escape_or_non_ascii() -> Result<()> {
+    let naive = |bytes: &[u8]| {
+        bytes
+            .iter()
+            .take_while(|&&b| b.is_ascii() && b != 0x1b)
+            .count()
+    };
+    // each stopping byte at each position, after and before the bytes the zero byte test could misreport
+    for len in 0..20 {
+        for at in 0..=len {
+            for stop in [0x1b, 0x80, 0xff, 0xe4] {
+                for filler in [b'a', 0x1a, 0x1c, 0x00, 0x7f] {
+                    let mut bytes = vec![filler; len];
+                    if at < len {
+                        bytes[at] = stop;
+                    }
+                    assert_eq!(plain_ascii_len(&bytes), naive(&bytes), "{bytes:x?}");
+                }
+            }
+        }
+    }
+    assert_eq!(plain_ascii_len("abcdefgh\u{1b}[0m".as_bytes()), 8);
+    assert_eq!(plain_ascii_len("abcdefghij\u{4e2d}".as_bytes()), 10);
+    Ok(())
+}
@@ -0,0 +1,380 @@
+//! A headless terminal screen, for checking what text containing escape sequences looks like when rendered.
+//!
+//! A [Screen] is a fixed size grid of cells, each holding one print position's grapheme and the [Style] it's
+//! rendered in.  Feeding it text applies the text's cursor motion, line wrap, scrolling and erase
+//! escape sequences the way a (simple) terminal emulator would, so a test can compare screen contents
+//! rather than raw escape sequences.
+//!
+//! ```rust
+//! use print_positions::screen::Screen;
+//!
+//! let mut screen = Screen::new(3, 20);
+//! screen.feed("Progress: 10%\r\x1b[KProgress: 100%\n\x1b[1mdone\x1b[0m");
+//! assert_eq!(screen.contents(), "Progress: 100%\ndone");
+//! assert!(screen.cell(1, 0).style().bold);
+//! assert_eq!(screen.cursor(), (1, 4));
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::escape::{escapes, Escape};
+use crate::style::Style;
+use crate::{print_position_info, TAB_STOP};
+
+#[cfg(test)]
+mod tests;
+
+/// One cell of a [Screen].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cell {
+    grapheme: String,
+    style: Style,
+}
+
+impl Cell {
+    fn blank(style: Style) -> Self {
+        Cell {
+            grapheme: " ".to_string(),
+            style,
+        }
+    }
+
+    // the right half of a double width grapheme
+    fn continuation(style: Style) -> Self {
+        Cell {
+            grapheme: String::new(),
+            style,
+        }
+    }
+
+    /// The grapheme displayed in the cell, `" "` for a blank cell.
+    ///
+    /// A double width grapheme occupies two cells: the left one holds the grapheme and the right one is empty.
+    #[inline]
+    pub fn grapheme(&self) -> &str {
+        &self.grapheme
+    }
+
+    /// Style the grapheme is rendered in.
+    #[inline]
+    pub fn style(&self) -> Style {
+        self.style
+    }
+
+    /// Whether this is the right half of a double width grapheme.
+    #[inline]
+    pub fn is_continuation(&self) -> bool {
+        self.grapheme.is_empty()
+    }
+}
+
+/// A grid of [Cell]s plus a cursor, updated by feeding it text.
+#[derive(Clone, Debug)]
+pub struct Screen {
+    rows: usize,
+    cols: usize,
+    cells: Vec<Vec<Cell>>,
+    // cursor row and column, always on screen
+    row: usize,
+    col: usize,
+    // cursor is past the last column, next grapheme wraps to next line
+    pending_wrap: bool,
+    style: Style,
+    saved_cursor: (usize, usize, Style),
+    newline_mode: bool,
+}
+
+impl Screen {
+    /// A blank screen `rows` high and `cols` wide with the cursor at the top left.
+    ///
+    /// Panics if either dimension is zero.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        assert!(rows > 0 && cols > 0, "screen must have at least one cell");
+        Screen {
+            rows,
+            cols,
+            cells: vec![vec![Cell::blank(Style::default()); cols]; rows],
+            row: 0,
+            col: 0,
+            pending_wrap: false,
+            style: Style::default(),
+            saved_cursor: (0, 0, Style::default()),
+            newline_mode: true,
+        }
+    }
+
+    /// Choose whether line feed (`\n`) also returns the cursor to the first column.
+    ///
+    /// It does by default, the way output written to a terminal is usually translated (`stty onlcr`).
+    /// Turn it off to treat line feed strictly as cursor down.
+    pub fn set_newline_mode(&mut self, on: bool) {
+        self.newline_mode = on;
+    }
+
+    /// Number of rows on the screen.
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns on the screen.
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Cursor position, as zero-based (row, column).
+    #[inline]
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
+    /// The cell at zero-based `row` and `col`.
+    ///
+    /// Panics if the cell is not on the screen.
+    #[inline]
+    pub fn cell(&self, row: usize, col: usize) -> &Cell {
+        &self.cells[row][col]
+    }
+
+    /// Text of one row, with trailing blanks removed.
+    pub fn row_text(&self, row: usize) -> String {
+        let text: String = self.cells[row].iter().map(|c| c.grapheme()).collect();
+        text.trim_end_matches(' ').to_string()
+    }
+
+    /// Text of the whole screen: the rows (see [Screen::row_text]) separated by newlines,
+    /// with trailing blank rows removed.
+    pub fn contents(&self) -> String {
+        let mut rows: Vec<String> = (0..self.rows).map(|r| self.row_text(r)).collect();
+        while rows.last().is_some_and(|r| r.is_empty()) {
+            rows.pop();
+        }
+        rows.join("\n")
+    }
+
+    /// Render `s` on the screen, starting at the current cursor position and style.
+    ///
+    /// Text is processed one print position at a time.  Supported controls are
+    /// CR, LF, BS, TAB and escape sequences for
+    /// * cursor motion: CUU, CUD, CUF, CUB, CNL, CPL, CHA, CUP, HVP, VPA, save and restore cursor,
+    /// * erasing: EL, ED,
+    /// * scrolling: SU, SD, IND, RI, NEL,
+    /// * rendition: SGR and full reset (RIS).
+    ///
+    /// Anything else is ignored.
+    pub fn feed(&mut self, s: &str) {
+        for p in print_position_info(s) {
+            self.apply_escapes(p.prefix_escapes());
+            let grapheme = p.grapheme();
+            if p.is_control() {
+                for c in grapheme.chars() {
+                    self.control(c);
+                }
+            } else if p.width() > 0 {
+                self.print(grapheme, p.width());
+            }
+            self.apply_escapes(p.suffix_escapes());
+        }
+    }
+
+    fn control(&mut self, c: char) {
+        match c {
+            '\r' => self.move_to(self.row, 0),
+            '\n' | '\x0b' | '\x0c' => {
+                self.line_feed();
+                if self.newline_mode {
+                    self.col = 0;
+                }
+            }
+            '\x08' => self.move_to(self.row, self.col.saturating_sub(1)),
+            '\t' => {
+                let col = (self.col / TAB_STOP + 1) * TAB_STOP;
+                self.move_to(self.row, col);
+            }
+            _ => {}
+        }
+    }
+
+    fn print(&mut self, grapheme: &str, width: usize) {
+        let width = width.min(self.cols);
+        if self.pending_wrap || self.col + width > self.cols {
+            if width > 1 && !self.pending_wrap {
+                // wide char doesn't fit on this line, leave last column blank
+                self.put(self.row, self.col, Cell::blank(self.style));
+            }
+            self.line_feed();
+            self.col = 0;
+        }
+        self.put(
+            self.row,
+            self.col,
+            Cell {
+                grapheme: grapheme.to_string(),
+                style: self.style,
+            },
+        );
+        for col in self.col + 1..self.col + width {
+            self.put(self.row, col, Cell::continuation(self.style));
+        }
+        self.col += width;
+        if self.col >= self.cols {
+            self.col = self.cols - 1;
+            self.pending_wrap = true;
+        }
+    }
+
+    // store a cell, blanking the other half of any double width grapheme it overwrites
+    fn put(&mut self, row: usize, col: usize, cell: Cell) {
+        let line = &mut self.cells[row];
+        if line[col].is_continuation() && col > 0 {
+            line[col - 1] = Cell::blank(line[col - 1].style);
+        }
+        if !cell.is_continuation() && col + 1 < self.cols && line[col + 1].is_continuation() {
+            line[col + 1] = Cell::blank(line[col + 1].style);
+        }
+        line[col] = cell;
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.rows - 1);
+        self.col = col.min(self.cols - 1);
+        self.pending_wrap = false;
+    }
+
+    fn line_feed(&mut self) {
+        if self.row + 1 == self.rows {
+            self.scroll_up(1);
+        } else {
+            self.row += 1;
+        }
+        self.pending_wrap = false;
+    }
+
+    fn blank_cell(&self) -> Cell {
+        // erased cells take the current background color, like most terminals
+        Cell::blank(Style {
+            background: self.style.background,
+            ..Style::default()
+        })
+    }
+
+    fn scroll_up(&mut self, n: usize) {
+        let n = n.min(self.rows);
+        let blank = vec![self.blank_cell(); self.cols];
+        self.cells.drain(..n);
+        self.cells.extend((0..n).map(|_| blank.clone()));
+    }
+
+    fn scroll_down(&mut self, n: usize) {
+        let n = n.min(self.rows);
+        let blank = vec![self.blank_cell(); self.cols];
+        self.cells.truncate(self.rows - n);
+        self.cells.splice(0..0, (0..n).map(|_| blank.clone()));
+    }
+
+    fn erase(&mut self, row: usize, cols: core::ops::Range<usize>) {
+        let blank = self.blank_cell();
+        for col in cols {
+            self.put(row, col, blank.clone());
+        }
+    }
+
+    fn apply_escapes(&mut self, escapes_str: &str) {
+        for escape in escapes(escapes_str) {
+            match escape {
+                Escape::Csi { params, final_byte } => self.csi(params, final_byte),
+                Escape::Simple(b'c') => {
+                    *self = Screen {
+                        newline_mode: self.newline_mode,
+                        ..Screen::new(self.rows, self.cols)
+                    }
+                }
+                Escape::Simple(b'D') => self.line_feed(),
+                Escape::Simple(b'E') => {
+                    self.line_feed();
+                    self.col = 0;
+                }
+                Escape::Simple(b'M') => {
+                    if self.row == 0 {
+                        self.scroll_down(1);
+                    } else {
+                        self.row -= 1;
+                    }
+                    self.pending_wrap = false;
+                }
+                Escape::Simple(b'7') => self.saved_cursor = (self.row, self.col, self.style),
+                Escape::Simple(b'8') => self.restore_cursor(),
+                _ => {}
+            }
+        }
+    }
+
+    fn restore_cursor(&mut self) {
+        let (row, col, style) = self.saved_cursor;
+        self.move_to(row, col);
+        self.style = style;
+    }
+
+    fn csi(&mut self, params: &str, final_byte: u8) {
+        if final_byte == b'm' {
+            self.style.apply_sgr(params);
+            return;
+        }
+        if params.starts_with(['?', '>', '<', '=']) {
+            return; // private modes aren't emulated
+        }
+        let args: Vec<usize> = params.split(';').map(|p| p.parse().unwrap_or(0)).collect();
+        // parameter `i`, or `default` if omitted or 0
+        let arg = |i: usize, default: usize| match args.get(i) {
+            Some(&0) | None => default,
+            Some(&n) => n,
+        };
+        let (row, col) = (self.row, self.col);
+        match final_byte {
+            b'A' => self.move_to(row.saturating_sub(arg(0, 1)), col),
+            b'B' => self.move_to(row.saturating_add(arg(0, 1)), col),
+            b'C' => self.move_to(row, col.saturating_add(arg(0, 1))),
+            b'D' => self.move_to(row, col.saturating_sub(arg(0, 1))),
+            b'E' => self.move_to(row.saturating_add(arg(0, 1)), 0),
+            b'F' => self.move_to(row.saturating_sub(arg(0, 1)), 0),
+            b'G' => self.move_to(row, arg(0, 1) - 1),
+            b'H' | b'f' => self.move_to(arg(0, 1) - 1, arg(1, 1) - 1),
+            b'd' => self.move_to(arg(0, 1) - 1, col),
+            b'J' => {
+                let (above, below) = match args.first() {
+                    None | Some(0) => (false, true),
+                    Some(1) => (true, false),
+                    Some(2) | Some(3) => (true, true),
+                    _ => (false, false),
+                };
+                if above {
+                    for r in 0..row {
+                        self.erase(r, 0..self.cols);
+                    }
+                    self.erase(row, 0..col + 1);
+                }
+                if below {
+                    self.erase(row, col..self.cols);
+                    for r in row + 1..self.rows {
+                        self.erase(r, 0..self.cols);
+                    }
+                }
+            }
+            b'K' => match args.first() {
+                None | Some(0) => self.erase(row, col..self.cols),
+                Some(1) => self.erase(row, 0..col + 1),
+                Some(2) => self.erase(row, 0..self.cols),
+                _ => {}
+            },
+            b'S' => self.scroll_up(arg(0, 1)),
+            b'T' => self.scroll_down(arg(0, 1)),
+            b's' => self.saved_cursor = (row, col, self.style),
+            b'u' => self.restore_cursor(),
+            _ => {}
+        }
+    }
+}
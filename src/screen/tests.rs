@@ -0,0 +1,111 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+fn render(rows: usize, cols: usize, input: &str) -> Screen {
+    let mut screen = Screen::new(rows, cols);
+    screen.feed(input);
+    screen
+}
+
+#[test]
+fn line_wrap_and_scroll() -> Result<()> {
+    let screen = render(2, 4, "abcdefghij");
+    assert_eq!(screen.contents(), "efgh\nij");
+    assert_eq!(screen.cursor(), (1, 2));
+
+    // cursor stays in last column until the next grapheme is printed
+    let screen = render(2, 4, "abcd");
+    assert_eq!(screen.cursor(), (0, 3));
+    assert_eq!(screen.contents(), "abcd");
+    Ok(())
+}
+
+#[test]
+fn wide_graphemes() -> Result<()> {
+    let screen = render(2, 5, "ab\u{4e2d}\u{4e2d}");
+    assert_eq!(screen.row_text(0), "ab\u{4e2d}");
+    assert_eq!(screen.row_text(1), "\u{4e2d}");
+    assert!(screen.cell(0, 3).is_continuation());
+
+    // overwriting half a wide grapheme blanks the other half
+    let screen = render(1, 5, "\u{4e2d}\u{4e2d}\x1b[2Gx");
+    assert_eq!(screen.row_text(0), " x\u{4e2d}");
+    Ok(())
+}
+
+#[test]
+fn cursor_motion() -> Result<()> {
+    let screen = render(3, 6, "\x1b[2;3Hx\x1b[Ay\x1b[2Bz\x1b[4Dw\x1b[1;6Hv\r\tq");
+    // tab stops at the last column
+    assert_eq!(screen.contents(), "   y q\n  x\n w  z");
+    let screen = render(2, 10, "abc\x1b[s\ndef\x1b[u!\x1b7\x1b[H?\x1b8*");
+    assert_eq!(screen.contents(), "?bc!*\ndef");
+    let screen = render(2, 10, "ab\x08\x08c\x1b[5G\x1b[1Ed");
+    assert_eq!(screen.contents(), "cb\nd");
+    Ok(())
+}
+
+#[test]
+fn erase() -> Result<()> {
+    let screen = render(3, 5, "aaaaabbbbbccccc\x1b[2;3H\x1b[K");
+    assert_eq!(screen.contents(), "aaaaa\nbb\nccccc");
+    let screen = render(3, 5, "aaaaabbbbbccccc\x1b[2;3H\x1b[1K");
+    assert_eq!(screen.contents(), "aaaaa\n   bb\nccccc");
+    let screen = render(3, 5, "aaaaabbbbbccccc\x1b[2;3H\x1b[J");
+    assert_eq!(screen.contents(), "aaaaa\nbb");
+    let screen = render(3, 5, "aaaaabbbbbccccc\x1b[2;3H\x1b[1J");
+    assert_eq!(screen.contents(), "\n   bb\nccccc");
+    let screen = render(3, 5, "aaaaabbbbbccccc\x1b[2J");
+    assert_eq!(screen.contents(), "");
+    Ok(())
+}
+
+#[test]
+fn styles_and_newline_mode() -> Result<()> {
+    let mut screen = render(2, 5, "\x1b[31;44ma\x1b[0mb\x1b[44m\x1b[K");
+    assert_eq!(
+        screen.cell(0, 0).style().foreground,
+        Some(crate::style::Color::Indexed(1))
+    );
+    assert!(screen.cell(0, 1).style().is_default());
+    assert_eq!(
+        screen.cell(0, 4).style().background,
+        Some(crate::style::Color::Indexed(4))
+    );
+
+    screen.set_newline_mode(false);
+    screen.feed("\x1b[0m\x1bc12\n34");
+    assert_eq!(screen.contents(), "12\n  34");
+    Ok(())
+}
+
+#[test]
+fn scrolling_sequences() -> Result<()> {
+    let screen = render(3, 3, "a\nb\nc\x1b[S");
+    assert_eq!(screen.contents(), "b\nc");
+    let screen = render(3, 3, "a\nb\nc\x1b[T");
+    assert_eq!(screen.contents(), "\na\nb");
+    let screen = render(3, 3, "a\x1bMb");
+    assert_eq!(screen.contents(), " b\na");
+    Ok(())
+}
+
+#[test]
+fn huge_parameters() -> Result<()> {
+    let max = usize::MAX;
+    for (escape, cursor) in [
+        (format!("\x1b[{max}B"), (2, 1)),
+        (format!("\x1b[{max}C"), (1, 3)),
+        (format!("\x1b[{max}E"), (2, 0)),
+        (format!("\x1b[{max}A\x1b[{max}D"), (0, 0)),
+        (format!("\x1b[{max};{max}H"), (2, 3)),
+        (format!("\x1b[{max}S\x1b[{max}T"), (1, 1)),
+    ] {
+        let screen = render(3, 4, &format!("a\nb{escape}"));
+        assert_eq!(screen.cursor(), cursor, "{escape:?}");
+    }
+    Ok(())
+}
@@ -0,0 +1,134 @@
+//! Configuration for segmenting and measuring print positions.
+
+use crate::lines::Lines;
+use crate::position::PrintPositionInfo;
+use crate::width::{self, WidthOverride, WidthProfile};
+use crate::{print_positions, PrintPositions};
+
+/// Settings which determine how print positions are measured.
+///
+/// The free functions of this crate, such as [print_position_info](crate::print_position_info) and
+/// [display_width](crate::display_width), use the default settings.  Create a `Segmenter` to choose
+/// others, then segment and measure through it.
+///
+/// ```rust
+/// use print_positions::width::{Segmenter, WidthProfile};
+///
+/// let segmenter = Segmenter::new().profile(WidthProfile::AmbiguousWide);
+/// let content = "\u{1b}[1m\u{b1}\u{d7}\u{1b}[0m";   // plus-minus and times signs are East Asian Ambiguous
+/// assert_eq!(segmenter.display_width(content), 4);
+/// let widths: Vec<_> = segmenter.print_position_info(content).map(|p| p.width()).collect();
+/// assert_eq!(widths, vec![2, 2]);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Segmenter<'a> {
+    profile: WidthProfile,
+    overrides: &'a [WidthOverride],
+    attach_zero_width: bool,
+}
+
+impl<'a> Segmenter<'a> {
+    /// A segmenter with the default settings.
+    pub const fn new() -> Self {
+        Segmenter {
+            profile: WidthProfile::Unicode15,
+            overrides: &[],
+            attach_zero_width: false,
+        }
+    }
+
+    /// Choose the rules for grapheme width.
+    pub const fn profile(mut self, profile: WidthProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Supply widths for particular characters, overriding the [WidthProfile].
+    /// The first override that applies to a grapheme wins.
+    pub const fn overrides(mut self, overrides: &'a [WidthOverride]) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Attach zero width graphemes to a neighboring print position, rather than segmenting them as
+    /// print positions of their own.
+    ///
+    /// Graphemes such as a zero width space, soft hyphen, byte order mark, bidi mark, bare ZWJ or combining mark
+    /// with nothing to combine with take no space on the screen (see [is_zero_width](Segmenter::is_zero_width)),
+    /// so counting them as print positions overcounts the columns a string occupies.
+    /// When attached, they're treated like escape sequences: included in the following print position,
+    /// or in the last one at the end of the string.
+    ///
+    /// ```rust
+    /// use print_positions::{print_positions, Segmenter};
+    ///
+    /// let content = "\u{feff}soft\u{ad}ware\u{200b}";
+    /// assert_eq!(print_positions(content).count(), 11);
+    ///
+    /// let segmenter = Segmenter::new().attach_zero_width(true);
+    /// assert_eq!(segmenter.print_positions(content).count(), 8);
+    /// let graphemes: Vec<_> = segmenter.print_position_info(content).map(|p| p.grapheme()).collect();
+    /// assert_eq!(graphemes, vec!["s", "o", "f", "t", "w", "a", "r", "e"]);
+    /// ```
+    pub const fn attach_zero_width(mut self, attach: bool) -> Self {
+        self.attach_zero_width = attach;
+        self
+    }
+
+    /// Iterate through the print positions of `s`, like [print_positions](crate::print_positions),
+    /// but attaching zero width graphemes if so configured.
+    pub fn print_positions<'s>(&self, s: &'s str) -> PrintPositions<'s>
+    where
+        'a: 's,
+    {
+        PrintPositions {
+            attach_zero_width: self.attach_zero_width.then_some(*self),
+            ..print_positions(s)
+        }
+    }
+
+    /// Iterate through the print positions of `s`.
+    /// Like [print_position_info](crate::print_position_info), but [PrintPosition::width](crate::PrintPosition::width)
+    /// uses this segmenter's settings.
+    pub fn print_position_info<'s>(&self, s: &'s str) -> PrintPositionInfo<'s>
+    where
+        'a: 's,
+    {
+        PrintPositionInfo::new(s, *self)
+    }
+
+    /// Iterate through the lines of `s`, like [lines](crate::lines::lines), measuring them with this segmenter's settings.
+    pub fn lines<'s>(&self, s: &'s str) -> Lines<'s>
+    where
+        'a: 's,
+    {
+        Lines::new(s, *self)
+    }
+
+    /// Number of columns `grapheme` occupies on the screen.
+    pub fn grapheme_width(&self, grapheme: &str) -> usize {
+        width::grapheme_width(grapheme, self.profile, self.overrides)
+    }
+
+    /// Whether `grapheme` takes no space on the screen, although it isn't a control character.
+    ///
+    /// ```rust
+    /// use print_positions::Segmenter;
+    ///
+    /// let segmenter = Segmenter::new();
+    /// assert!(segmenter.is_zero_width("\u{200b}"));    // zero width space
+    /// assert!(segmenter.is_zero_width("\u{301}"));     // combining mark, nothing to combine with
+    /// assert!(!segmenter.is_zero_width("\n"));         // no space, but a control character
+    /// assert!(!segmenter.is_zero_width("e\u{301}"));
+    /// ```
+    pub fn is_zero_width(&self, grapheme: &str) -> bool {
+        !grapheme.is_empty()
+            && !grapheme.starts_with(char::is_control)
+            && self.grapheme_width(grapheme) == 0
+    }
+
+    /// Number of columns `s` occupies on the screen, the sum of the widths of its print positions.
+    pub fn display_width(&self, s: &str) -> usize {
+        self.print_position_info(s).map(|p| p.width()).sum()
+    }
+}
@@ -0,0 +1,210 @@
+//! Serialization of styled text with [serde](https://serde.rs), built with feature `serde`.
+//!
+//! With this feature, the style types ([Style], [Color](crate::style::Color)) and the span types
+//! ([Coordinate](crate::coordinates::Coordinate), [Hit](crate::coordinates::Hit) and, with feature `alloc`,
+//! [Cell](crate::screen::Cell)) implement `Serialize` and `Deserialize`.
+//! [Hyperlink](crate::hyperlink::Hyperlink) only implements `Serialize`, since it borrows from the text it was found in.
+//!
+//! This module, which also needs feature `alloc`, serializes a string for a program without an ANSI parser
+//! (say, a web viewer for stored logs).  [StyledPositions] serializes it as a sequence of its print positions,
+//! each with the style its escape sequences select and its display width, and [Tokens] as a sequence of
+//! graphemes and escape sequences, each with its [TokenKind].  Text which deserialization can't borrow
+//! (because JSON escapes it, like a tab or ESC) is deserialized into an owned `String`.
+//!
+//! ```rust
+//! use print_positions::serialize::styled_positions;
+//!
+//! let json = serde_json::to_string(&styled_positions("a\u{1b}[1m\u{4e2d}\u{1b}[0m"))?;
+//! assert_eq!(
+//!     json,
+//!     concat!(
+//!         r#"[{"text":"a","style":{"foreground":null,"background":null,"bold":false,"#,
+//!         r#""dim":false,"italic":false,"underline":false,"blink":false,"inverse":false,"#,
+//!         r#""hidden":false,"strikethrough":false},"width":1},"#,
+//!         r#"{"text":"中","style":{"foreground":null,"background":null,"bold":true,"#,
+//!         r#""dim":false,"italic":false,"underline":false,"blink":false,"inverse":false,"#,
+//!         r#""hidden":false,"strikethrough":false},"width":2}]"#
+//!     )
+//! );
+//! # Ok::<(), serde_json::Error>(())
+//! ```
+
+use alloc::borrow::Cow;
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::escape::{self, Escape};
+use crate::style::Style;
+use crate::{print_position_info, print_positions, Parts, PrintPositionInfo, PrintPositions};
+
+#[cfg(test)]
+mod tests;
+
+/// A print position reduced to what it displays: its grapheme, in its style, taking its width.
+/// Returned by iterator [StyledPositions].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StyledPosition<'a> {
+    /// The grapheme, without escape sequences.
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+    /// Style the grapheme is rendered in.
+    pub style: Style,
+    /// Display width of the grapheme.
+    pub width: usize,
+}
+
+/// This iterator returns each print position found in a string as a [StyledPosition].
+///
+/// It segments the string exactly the same way as [PrintPositionData](crate::PrintPositionData),
+/// and tracks the [Style] selected by the escape sequences of each print position, as they render it.
+/// It implements `Serialize` as the sequence of print positions yet to be returned.
+#[derive(Clone)]
+pub struct StyledPositions<'a> {
+    iter: PrintPositionInfo<'a>,
+    // style in effect after the last print position returned
+    style: Style,
+}
+
+/// Factory method to provide a new [StyledPositions] iterator.
+#[inline]
+pub fn styled_positions(s: &str) -> StyledPositions<'_> {
+    StyledPositions {
+        iter: print_position_info(s),
+        style: Style::default(),
+    }
+}
+
+impl<'a> StyledPositions<'a> {
+    /// View the underlying data (the part yet to be iterated) as a slice of the original string.
+    #[inline]
+    pub fn as_str(&self) -> &'a str {
+        self.iter.as_str()
+    }
+}
+
+impl<'a> Iterator for StyledPositions<'a> {
+    type Item = StyledPosition<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let p = self.iter.next()?;
+        self.style.apply_escapes(p.prefix_escapes());
+        let retval = StyledPosition {
+            text: Cow::Borrowed(p.grapheme()),
+            style: self.style,
+            width: p.width(),
+        };
+        self.style.apply_escapes(p.suffix_escapes());
+        Some(retval)
+    }
+}
+
+impl Serialize for StyledPositions<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
+    }
+}
+
+/// What a [Token] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TokenKind {
+    /// A grapheme, which is displayed.
+    Grapheme,
+    /// A Control Sequence Introducer escape sequence, such as SGR `ESC [ 1 m`.
+    Csi,
+    /// An Operating System Command escape sequence, such as an OSC 8 hyperlink.
+    Osc,
+    /// Any other escape sequence.
+    Escape,
+}
+
+/// A grapheme or an escape sequence.  Returned by iterator [Tokens].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Token<'a> {
+    /// What the text is.
+    pub kind: TokenKind,
+    /// The grapheme or the whole escape sequence.
+    #[serde(borrow)]
+    pub text: Cow<'a, str>,
+}
+
+/// This iterator splits a string into [Token]s: the graphemes and escape sequences of its print positions, in order.
+///
+/// It segments the string exactly the same way as [PrintPositionData](crate::PrintPositionData), so the text of the
+/// tokens, concatenated, is the string.  It implements `Serialize` as the sequence of tokens yet to be returned.
+///
+/// ```rust
+/// use print_positions::serialize::{tokens, TokenKind};
+///
+/// let kinds: Vec<_> = tokens("\u{1b}[1ma\u{1b}]8;;http://x\u{7}b").map(|t| t.kind).collect();
+/// assert_eq!(
+///     kinds,
+///     vec![TokenKind::Csi, TokenKind::Grapheme, TokenKind::Osc, TokenKind::Grapheme]
+/// );
+/// ```
+#[derive(Clone)]
+pub struct Tokens<'a> {
+    iter: PrintPositions<'a>,
+    // the print position being split, and the offset of the rest of it
+    parts: Parts,
+    offset: usize,
+}
+
+/// Factory method to provide a new [Tokens] iterator.
+#[inline]
+pub fn tokens(s: &str) -> Tokens<'_> {
+    Tokens {
+        iter: print_positions(s),
+        parts: Parts {
+            start: 0,
+            grapheme_start: 0,
+            grapheme_end: 0,
+            end: 0,
+        },
+        offset: 0,
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset == self.parts.end {
+            self.parts = self.iter.next_parts()?;
+            self.offset = self.parts.start;
+        }
+        let string = self.iter.string;
+        let parts = self.parts;
+        let start = self.offset;
+        if start == parts.grapheme_start && parts.grapheme_end > parts.grapheme_start {
+            self.offset = parts.grapheme_end;
+            return Some(Token {
+                kind: TokenKind::Grapheme,
+                text: Cow::Borrowed(&string[start..parts.grapheme_end]),
+            });
+        }
+        // escape sequences before or after the grapheme
+        let end = if start < parts.grapheme_start {
+            parts.grapheme_start
+        } else {
+            parts.end
+        };
+        let (escape, len) = escape::parse(&string[start..end]);
+        self.offset += len;
+        let kind = match escape {
+            Escape::Csi { .. } => TokenKind::Csi,
+            Escape::Osc(_) => TokenKind::Osc,
+            Escape::Simple(_) => TokenKind::Escape,
+        };
+        Some(Token {
+            kind,
+            text: Cow::Borrowed(&string[start..self.offset]),
+        })
+    }
+}
+
+impl Serialize for Tokens<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.clone())
+    }
+}
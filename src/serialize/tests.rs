@@ -0,0 +1,139 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::coordinates::{position_at_column, Coordinates};
+use crate::style::Color;
+
+const CONTENT: &str = "\u{1b}[31m\u{1b}]8;;http://x\u{1b}\\a\u{1b}[1m\u{4e2d}\u{1b}[0m\u{1b}]8;;\u{1b}\\e\u{301}\u{1b}[44m";
+
+#[test]
+fn positions() -> Result<()> {
+    let red = Style {
+        foreground: Some(Color::Indexed(1)),
+        ..Style::default()
+    };
+    let positions: Vec<_> = styled_positions(CONTENT).collect();
+    assert_eq!(
+        positions,
+        vec![
+            StyledPosition {
+                text: Cow::Borrowed("a"),
+                style: red,
+                width: 1
+            },
+            StyledPosition {
+                text: Cow::Borrowed("\u{4e2d}"),
+                style: Style { bold: true, ..red },
+                width: 2
+            },
+            StyledPosition {
+                text: Cow::Borrowed("e\u{301}"),
+                style: Style::default(),
+                width: 1
+            },
+        ]
+    );
+    assert_eq!(positions.len(), crate::print_position_data(CONTENT).count());
+    Ok(())
+}
+
+#[test]
+fn round_trip() -> Result<()> {
+    let json = serde_json::to_string(&styled_positions(CONTENT))?;
+    let back: Vec<StyledPosition> = serde_json::from_str(&json)?;
+    assert_eq!(back, styled_positions(CONTENT).collect::<Vec<_>>());
+
+    // serializes what's left to iterate
+    let mut rest = styled_positions(CONTENT);
+    rest.next();
+    let json = serde_json::to_string(&rest)?;
+    assert!(json.starts_with(r#"[{"text":"中","#), "{json}");
+    assert_eq!(rest.count(), 2);
+
+    assert_eq!(serde_json::to_string(&styled_positions(""))?, "[]");
+    Ok(())
+}
+
+#[test]
+fn round_trip_json_escapes() -> Result<()> {
+    // JSON escapes these, so deserializing can't borrow them
+    let content = "\t\"\\\u{1b}[1mx\u{1b}";
+    let json = serde_json::to_string(&styled_positions(content))?;
+    let back: Vec<StyledPosition> = serde_json::from_str(&json)?;
+    assert_eq!(back, styled_positions(content).collect::<Vec<_>>());
+    assert_eq!(back[0].text, "\t");
+
+    let json = serde_json::to_string(&tokens(content))?;
+    let back: Vec<Token> = serde_json::from_str(&json)?;
+    assert_eq!(back, tokens(content).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn token_kinds() -> Result<()> {
+    let observed: Vec<_> = tokens(CONTENT).collect();
+    let text: String = observed.iter().map(|t| t.text.as_ref()).collect();
+    assert_eq!(text, CONTENT);
+    let kinds: Vec<_> = observed.iter().map(|t| t.kind).collect();
+    use TokenKind::*;
+    assert_eq!(
+        kinds,
+        vec![Csi, Osc, Grapheme, Csi, Grapheme, Csi, Osc, Grapheme, Csi]
+    );
+
+    let kinds: Vec<_> = tokens("\u{1b}cx\u{1b}").map(|t| t.kind).collect();
+    assert_eq!(kinds, vec![Escape, Grapheme, Escape]);
+    assert_eq!(tokens("").count(), 0);
+
+    assert_eq!(serde_json::to_string(&Osc)?, r#""Osc""#);
+    assert_eq!(
+        serde_json::to_string(&tokens("a\u{1b}[m"))?,
+        r#"[{"kind":"Grapheme","text":"a"},{"kind":"Csi","text":"\u001b[m"}]"#
+    );
+    Ok(())
+}
+
+#[test]
+fn span_types() -> Result<()> {
+    let coordinates = Coordinates::new(CONTENT);
+    let end = coordinates.end();
+    let json = serde_json::to_string(&end)?;
+    assert_eq!(
+        json,
+        r#"{"byte":47,"utf16":44,"char":44,"index":3,"column":4}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<crate::coordinates::Coordinate>(&json)?,
+        end
+    );
+
+    let hit = position_at_column(CONTENT, 2).context("hit")?;
+    let json = serde_json::to_string(&hit)?;
+    assert_eq!(
+        json,
+        r#"{"index":1,"byte_range":{"start":21,"end":32},"offset_within_glyph":1}"#
+    );
+    assert_eq!(serde_json::from_str::<crate::coordinates::Hit>(&json)?, hit);
+
+    let link = crate::print_position_info(CONTENT)
+        .next()
+        .and_then(|p| p.hyperlink())
+        .context("link")?;
+    assert_eq!(
+        serde_json::to_string(&link)?,
+        r#"{"params":"","uri":"http://x"}"#
+    );
+
+    let style = Style {
+        background: Some(Color::Rgb(1, 2, 3)),
+        italic: true,
+        ..Style::default()
+    };
+    let json = serde_json::to_string(&style)?;
+    assert!(json.contains(r#""background":{"Rgb":[1,2,3]}"#), "{json}");
+    assert_eq!(serde_json::from_str::<Style>(&json)?, style);
+    Ok(())
+}
@@ -0,0 +1,282 @@
+//! Rendering style (color and emphasis) selected by ANSI SGR escape sequences.
+//!
+//! The print position iterators pass escape sequences through untouched.  A [Style] tracks what
+//! they mean, so you can tell how a print position will be rendered.
+//!
+//! ```rust
+//! use print_positions::print_position_info;
+//! use print_positions::style::{Color, Style};
+//!
+//! let mut style = Style::default();
+//! let mut rendered = vec![];
+//! for p in print_position_info("a\u{1b}[1;31mb\u{1b}[0mc") {
+//!     style.apply_escapes(p.prefix_escapes());
+//!     rendered.push((p.grapheme(), style));
+//!     style.apply_escapes(p.suffix_escapes());
+//! }
+//! assert_eq!(rendered[0], ("a", Style::default()));
+//! assert_eq!(rendered[1].1.foreground, Some(Color::Indexed(1)));
+//! assert!(rendered[1].1.bold);
+//! assert_eq!(rendered[2], ("c", Style::default()));
+//! ```
+
+use core::fmt;
+
+use crate::escape::{escapes, Escape};
+
+#[cfg(test)]
+mod tests;
+
+/// A color selected by an SGR escape sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Color {
+    /// Palette color.  0-7 are the standard colors (`ESC[30m` - `ESC[37m`), 8-15 the bright ones (`ESC[90m` - `ESC[97m`),
+    /// 16-255 the rest of the 256 color palette (`ESC[38;5;<n>m`).
+    Indexed(u8),
+    /// 24 bit color (`ESC[38;2;<r>;<g>;<b>m`)
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /// Red, green and blue components of the color, using the xterm default palette for [Color::Indexed].
+    ///
+    /// ```rust
+    /// # use print_positions::style::Color;
+    /// assert_eq!(Color::Indexed(1).to_rgb(), (0xcd, 0, 0));
+    /// assert_eq!(Color::Indexed(196).to_rgb(), (0xff, 0, 0));
+    /// assert_eq!(Color::Rgb(1, 2, 3).to_rgb(), (1, 2, 3));
+    /// ```
+    pub fn to_rgb(self) -> (u8, u8, u8) {
+        const BASIC: [(u8, u8, u8); 16] = [
+            (0x00, 0x00, 0x00),
+            (0xcd, 0x00, 0x00),
+            (0x00, 0xcd, 0x00),
+            (0xcd, 0xcd, 0x00),
+            (0x00, 0x00, 0xee),
+            (0xcd, 0x00, 0xcd),
+            (0x00, 0xcd, 0xcd),
+            (0xe5, 0xe5, 0xe5),
+            (0x7f, 0x7f, 0x7f),
+            (0xff, 0x00, 0x00),
+            (0x00, 0xff, 0x00),
+            (0xff, 0xff, 0x00),
+            (0x5c, 0x5c, 0xff),
+            (0xff, 0x00, 0xff),
+            (0x00, 0xff, 0xff),
+            (0xff, 0xff, 0xff),
+        ];
+        match self {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::Indexed(n @ 0..=15) => BASIC[n as usize],
+            Color::Indexed(n @ 16..=231) => {
+                let level = |v: u8| if v == 0 { 0 } else { 55 + 40 * v };
+                let n = n - 16;
+                (level(n / 36), level((n / 6) % 6), level(n % 6))
+            }
+            Color::Indexed(n) => {
+                let gray = 8 + 10 * (n - 232);
+                (gray, gray, gray)
+            }
+        }
+    }
+}
+
+/// Rendering style of text: colors and emphasis.
+///
+/// The default style is the terminal's default rendering: no colors specified and no emphasis.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Style {
+    /// Foreground (text) color, `None` for the terminal default.
+    pub foreground: Option<Color>,
+    /// Background color, `None` for the terminal default.
+    pub background: Option<Color>,
+    /// Bold or increased intensity (SGR 1).
+    pub bold: bool,
+    /// Faint or decreased intensity (SGR 2).
+    pub dim: bool,
+    /// Italic (SGR 3).
+    pub italic: bool,
+    /// Underline (SGR 4, or double underline SGR 21).
+    pub underline: bool,
+    /// Blink (SGR 5 or 6).
+    pub blink: bool,
+    /// Swap foreground and background colors (SGR 7).
+    pub inverse: bool,
+    /// Hidden text (SGR 8).
+    pub hidden: bool,
+    /// Strikethrough (SGR 9).
+    pub strikethrough: bool,
+}
+
+impl Style {
+    /// Whether this is the default style.
+    #[inline]
+    pub fn is_default(&self) -> bool {
+        *self == Style::default()
+    }
+
+    /// Update the style for all the SGR sequences found in `escapes`, in order.
+    ///
+    /// Other escape sequences are ignored, except for full reset (`ESC c`) which resets the style.
+    /// `escapes` is typically [PrintPosition::prefix_escapes](crate::PrintPosition::prefix_escapes)
+    /// or [PrintPosition::suffix_escapes](crate::PrintPosition::suffix_escapes).
+    pub fn apply_escapes(&mut self, escapes_str: &str) {
+        for escape in escapes(escapes_str) {
+            match escape {
+                Escape::Csi {
+                    params,
+                    final_byte: b'm',
+                } => self.apply_sgr(params),
+                Escape::Simple(b'c') => *self = Style::default(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Update the style for the parameters of one SGR sequence, e.g `"1;31"` for `ESC[1;31m`.
+    ///
+    /// ```rust
+    /// # use print_positions::style::{Color, Style};
+    /// let mut style = Style::default();
+    /// style.apply_sgr("1;38;5;208");
+    /// assert!(style.bold);
+    /// assert_eq!(style.foreground, Some(Color::Indexed(208)));
+    /// style.apply_sgr("");    // same as "0"
+    /// assert!(style.is_default());
+    /// ```
+    pub fn apply_sgr(&mut self, params: &str) {
+        let mut params = params.split(';');
+
+        while let Some(param) = params.next() {
+            // colon-separated subparameters belong to this parameter alone
+            let (code, subparams) = match param.split_once(':') {
+                Some((code, subparams)) => (code, Some(subparams)),
+                None => (param, None),
+            };
+            let code = if code.is_empty() {
+                0
+            } else {
+                let Ok(code) = code.parse::<u16>() else {
+                    continue;
+                };
+                code
+            };
+            match code {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 | 21 => self.underline = true,
+                5 | 6 => self.blink = true,
+                7 => self.inverse = true,
+                8 => self.hidden = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                25 => self.blink = false,
+                27 => self.inverse = false,
+                28 => self.hidden = false,
+                29 => self.strikethrough = false,
+                30..=37 => self.foreground = Some(Color::Indexed((code - 30) as u8)),
+                38 => self.foreground = extended_color(subparams, &mut params).or(self.foreground),
+                39 => self.foreground = None,
+                40..=47 => self.background = Some(Color::Indexed((code - 40) as u8)),
+                48 => self.background = extended_color(subparams, &mut params).or(self.background),
+                49 => self.background = None,
+                90..=97 => self.foreground = Some(Color::Indexed((code - 90 + 8) as u8)),
+                100..=107 => self.background = Some(Color::Indexed((code - 100 + 8) as u8)),
+                _ => {}
+            }
+        }
+    }
+
+    /// An escape sequence which sets this style from any prior style.
+    ///
+    /// ```rust
+    /// # use print_positions::style::{Color, Style};
+    /// let style = Style { bold: true, foreground: Some(Color::Indexed(9)), ..Style::default() };
+    /// assert_eq!(style.sgr().to_string(), "\u{1b}[0;1;91m");
+    /// assert_eq!(Style::default().sgr().to_string(), "\u{1b}[0m");
+    /// ```
+    pub fn sgr(&self) -> impl fmt::Display + '_ {
+        Sgr(self)
+    }
+}
+
+// The color selected by SGR 38 or 48: from its own `subparams`, e.g. `5:208` or `2::10:20:30` (the color space
+// may be left out), or else from the parameters following it, e.g. `;5;208` or `;2;10;20;30`.
+fn extended_color<'a>(
+    subparams: Option<&'a str>,
+    params: &mut impl Iterator<Item = &'a str>,
+) -> Option<Color> {
+    // an omitted value is 0
+    let value = |v: &str| match v {
+        "" => Some(0),
+        v => v.parse::<u16>().ok().map(|v| v.min(255) as u8),
+    };
+    match subparams {
+        Some(subparams) => {
+            let mut subparams = subparams.split(':');
+            match subparams.next()? {
+                "5" => Some(Color::Indexed(value(subparams.next()?)?)),
+                "2" => {
+                    let mut rgb = [subparams.next()?, subparams.next()?, subparams.next()?];
+                    if let Some(b) = subparams.next() {
+                        // the first was the color space
+                        rgb = [rgb[1], rgb[2], b];
+                    }
+                    Some(Color::Rgb(value(rgb[0])?, value(rgb[1])?, value(rgb[2])?))
+                }
+                _ => None,
+            }
+        }
+        None => {
+            let mut component = || params.next().and_then(value);
+            match component()? {
+                5 => Some(Color::Indexed(component()?)),
+                2 => Some(Color::Rgb(component()?, component()?, component()?)),
+                _ => None,
+            }
+        }
+    }
+}
+
+struct Sgr<'a>(&'a Style);
+
+impl fmt::Display for Sgr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let style = self.0;
+        f.write_str("\x1b[0")?;
+        for (on, code) in [
+            (style.bold, 1),
+            (style.dim, 2),
+            (style.italic, 3),
+            (style.underline, 4),
+            (style.blink, 5),
+            (style.inverse, 7),
+            (style.hidden, 8),
+            (style.strikethrough, 9),
+        ] {
+            if on {
+                write!(f, ";{code}")?;
+            }
+        }
+        for (color, base, bright_base) in [(style.foreground, 30, 90), (style.background, 40, 100)]
+        {
+            match color {
+                None => {}
+                Some(Color::Indexed(n @ 0..=7)) => write!(f, ";{}", base + n as u16)?,
+                Some(Color::Indexed(n @ 8..=15)) => write!(f, ";{}", bright_base + n as u16 - 8)?,
+                Some(Color::Indexed(n)) => write!(f, ";{};5;{n}", base + 8)?,
+                Some(Color::Rgb(r, g, b)) => write!(f, ";{};2;{r};{g};{b}", base + 8)?,
+            }
+        }
+        f.write_str("m")
+    }
+}
@@ -0,0 +1,115 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[test]
+fn sgr_attributes_on_and_off() -> Result<()> {
+    let mut style = Style::default();
+    style.apply_sgr("1;2;3;4;5;7;8;9");
+    assert!(style.bold && style.dim && style.italic && style.underline);
+    assert!(style.blink && style.inverse && style.hidden && style.strikethrough);
+    style.apply_sgr("22;23;24;25;27;28;29");
+    assert!(style.is_default());
+    Ok(())
+}
+
+#[test]
+fn sgr_colors() -> Result<()> {
+    let cases = [
+        ("31", Some(Color::Indexed(1)), None),
+        ("92;44", Some(Color::Indexed(10)), Some(Color::Indexed(4))),
+        (
+            "38;5;123;107",
+            Some(Color::Indexed(123)),
+            Some(Color::Indexed(15)),
+        ),
+        ("48;2;10;20;30", None, Some(Color::Rgb(10, 20, 30))),
+        ("38:5:200", Some(Color::Indexed(200)), None),
+        ("48:2::10:20:30", None, Some(Color::Rgb(10, 20, 30))),
+        ("48:2:10:20:30", None, Some(Color::Rgb(10, 20, 30))),
+        (
+            "38:5:200;44",
+            Some(Color::Indexed(200)),
+            Some(Color::Indexed(4)),
+        ),
+        ("38:7:1;44", None, Some(Color::Indexed(4))),
+        ("31;39", None, None),
+    ];
+    for (params, fg, bg) in cases {
+        let mut style = Style::default();
+        style.apply_sgr(params);
+        assert_eq!((fg, bg), (style.foreground, style.background), "{params}");
+    }
+    Ok(())
+}
+
+#[test]
+fn colon_color_followed_by_attributes() -> Result<()> {
+    let mut style = Style::default();
+    style.apply_escapes("\x1b[38:5:208;1m");
+    assert_eq!(style.foreground, Some(Color::Indexed(208)));
+    assert!(style.bold);
+
+    let mut style = Style::default();
+    style.apply_sgr("48:2::1:2:3;3;4");
+    assert_eq!(style.background, Some(Color::Rgb(1, 2, 3)));
+    assert!(style.italic && style.underline);
+
+    // the legacy form takes the parameters after it
+    let mut style = Style::default();
+    style.apply_sgr("38;5;208;1");
+    assert_eq!(style.foreground, Some(Color::Indexed(208)));
+    assert!(style.bold);
+    Ok(())
+}
+
+#[test]
+fn apply_escapes_ignores_other_sequences() -> Result<()> {
+    let mut style = Style::default();
+    style.apply_escapes("\x1b[2J\x1b[1m\x1b]0;title\x07\x1b[4m");
+    assert_eq!(
+        Style {
+            bold: true,
+            underline: true,
+            ..Style::default()
+        },
+        style
+    );
+    style.apply_escapes("\x1bc");
+    assert!(style.is_default());
+    Ok(())
+}
+
+#[test]
+fn sgr_round_trip() -> Result<()> {
+    let style = Style {
+        foreground: Some(Color::Rgb(1, 2, 3)),
+        background: Some(Color::Indexed(100)),
+        italic: true,
+        inverse: true,
+        ..Style::default()
+    };
+    let mut observed = Style {
+        bold: true,
+        ..Style::default()
+    };
+    observed.apply_escapes(&style.sgr().to_string());
+    assert_eq!(style, observed);
+    Ok(())
+}
+
+#[test]
+fn escapes_end_where_print_positions_end_them() -> Result<()> {
+    // the combining mark joins the '[', so the CSI is ended by the next ESC, leaving ']' a visible grapheme
+    let content = "\u{1b}[\u{301}\u{1b}]a\u{1b}[1m";
+    let graphemes: String = crate::print_position_info(content)
+        .map(|p| p.grapheme())
+        .collect();
+    assert_eq!(graphemes, "]a");
+    let mut style = Style::default();
+    style.apply_escapes(content);
+    assert!(style.bold);
+    Ok(())
+}
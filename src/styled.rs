@@ -0,0 +1,248 @@
+//! An owned string of styled text, edited by print position.
+//!
+//! ```rust
+//! use print_positions::styled::StyledString;
+//!
+//! let mut s = StyledString::from("\u{1b}[31mred\u{1b}[0m text");
+//! assert_eq!(s.len(), 8);
+//! s.insert(3, ",");
+//! s.replace(5..9, "words");
+//! assert_eq!(s.as_str(), "\u{1b}[31mred\u{1b}[0m, words");
+//!
+//! // removing the start of the red text doesn't lose the color of the rest of it
+//! s.remove(0..1);
+//! assert_eq!(s.as_str(), "\u{1b}[0;31med\u{1b}[0m, words");
+//! ```
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use crate::rendition::Rendition;
+use crate::{print_positions, PrintPositionInfo, Segmenter};
+
+#[cfg(test)]
+mod tests;
+
+/// Text with escape sequences, together with an index of its print positions.
+///
+/// Print positions are found when the text changes, so [StyledString::len] and [StyledString::get] are cheap.
+/// An edit re-segments only the text around it, but an edit before the end still scans the escape sequences
+/// before it for the style and hyperlink in effect there, and moves the text after it, so it takes time proportional
+/// to the length of the string.  [StyledString::push] doesn't, so building a string up piece by piece takes linear time.
+/// Edits address print positions rather than bytes, and keep the escape sequences correct:
+/// * Escape sequences in inserted text affect only the inserted text: the text after it keeps its own style and hyperlink.
+/// * Removed text takes its graphemes and escape sequences with it, but the text after it keeps its own style and hyperlink.
+///
+/// To do that, an edit may add SGR and OSC 8 escape sequences after the changed text.
+/// Text appended at the end is left as is, though, so escape sequences pushed on their own apply to text pushed later.
+/// Escape sequences which don't affect style or hyperlink (such as cursor motion) are removed along with their print positions.
+///
+/// Edits re-segment the text, so the inserted text may combine with its neighbors (for example, a combining mark
+/// joins the grapheme before it).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StyledString {
+    text: String,
+    // start offset of each print position
+    starts: Vec<usize>,
+}
+
+impl StyledString {
+    /// An empty string.
+    pub fn new() -> Self {
+        StyledString::default()
+    }
+
+    /// The text, with its escape sequences.
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        &self.text
+    }
+
+    /// Take the text, with its escape sequences.
+    #[inline]
+    pub fn into_string(self) -> String {
+        self.text
+    }
+
+    /// Number of print positions.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Whether there are no print positions (and so no text).
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.starts.is_empty()
+    }
+
+    /// Print position `index`, or `None` if there are not that many.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        (index < self.len()).then(|| &self.text[self.offset(index)..self.offset(index + 1)])
+    }
+
+    /// Start and end + 1 byte offsets of print positions `range.start` up to (but not including) `range.end`.
+    ///
+    /// Panics if the range extends past the end of the string.
+    pub fn byte_range(&self, range: Range<usize>) -> Range<usize> {
+        self.check(&range);
+        self.offset(range.start)..self.offset(range.end)
+    }
+
+    /// Iterate through the print positions as [PrintPosition](crate::PrintPosition)s.
+    #[inline]
+    pub fn print_position_info(&self) -> PrintPositionInfo<'_> {
+        crate::print_position_info(&self.text)
+    }
+
+    /// Number of columns the text occupies on the screen.  See [display_width](crate::display_width).
+    #[inline]
+    pub fn width(&self) -> usize {
+        Segmenter::new().display_width(&self.text)
+    }
+
+    /// Append `s`.  Only the last print position and `s` are segmented.
+    pub fn push(&mut self, s: &str) {
+        self.replace(self.len()..self.len(), s);
+    }
+
+    /// Insert `s` before print position `index`.
+    ///
+    /// Panics if `index` is greater than the number of print positions.
+    pub fn insert(&mut self, index: usize, s: &str) {
+        self.replace(index..index, s);
+    }
+
+    /// Remove print positions `range.start` up to (but not including) `range.end`.
+    ///
+    /// Panics if the range extends past the end of the string.
+    pub fn remove(&mut self, range: Range<usize>) {
+        self.replace(range, "");
+    }
+
+    /// Replace print positions `range.start` up to (but not including) `range.end` with `s`.
+    ///
+    /// Panics if the range extends past the end of the string.
+    pub fn replace(&mut self, range: Range<usize>, s: &str) {
+        let bytes = self.byte_range(range.clone());
+
+        let mut replacement = String::from(s);
+        if !(bytes.is_empty() && bytes.end == self.text.len()) {
+            let mut before = Rendition::default();
+            before.apply(&self.text[..bytes.start]);
+            let mut after = before;
+            after.apply(&self.text[bytes.clone()]);
+            let mut inserted = before;
+            inserted.apply(s);
+            inserted
+                .write_transition(&after, &mut replacement)
+                .expect("writing to a String");
+        }
+        // The edit can change where the print position before it ends (e.g. a combining mark joins its grapheme),
+        // but nothing before that.
+        let first = range.start.saturating_sub(1);
+        let from = self.offset(first);
+        self.text.replace_range(bytes.clone(), &replacement);
+        self.reindex(
+            from,
+            first..range.end,
+            bytes.end,
+            bytes.start + replacement.len(),
+        );
+    }
+
+    /// Split into print positions before `index` and those from `index` on.
+    ///
+    /// Like [text::slice](crate::text::slice), the first part closes the style and hyperlink in effect at the split,
+    /// and the second reopens them.
+    /// Panics if `index` is greater than the number of print positions.
+    ///
+    /// ```rust
+    /// use print_positions::styled::StyledString;
+    ///
+    /// let s = StyledString::from("\u{1b}[1mbold\u{1b}[0m");
+    /// let (left, right) = s.split_at(2);
+    /// assert_eq!(left.as_str(), "\u{1b}[1mbo\u{1b}[0m");
+    /// assert_eq!(right.as_str(), "\u{1b}[0;1mld\u{1b}[0m");
+    /// ```
+    pub fn split_at(&self, index: usize) -> (StyledString, StyledString) {
+        let at = self.byte_range(index..index).start;
+        let mut state = Rendition::default();
+        state.apply(&self.text[..at]);
+
+        let mut left = String::from(&self.text[..at]);
+        state.write_close(&mut left).expect("writing to a String");
+        let mut right = String::new();
+        state.write_open(&mut right).expect("writing to a String");
+        right.push_str(&self.text[at..]);
+        (StyledString::from(left), StyledString::from(right))
+    }
+
+    // byte offset of print position `index`, or the end of the text
+    fn offset(&self, index: usize) -> usize {
+        self.starts.get(index).copied().unwrap_or(self.text.len())
+    }
+
+    fn check(&self, range: &Range<usize>) {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "print position range {range:?} out of bounds for length {}",
+            self.len()
+        );
+    }
+
+    // Re-segment from byte `from`, the start of print position `replaced.start`, after the text of the print positions
+    // `replaced` up to byte `old_end` was replaced with text up to byte `new_end`.  Once the new print positions
+    // line up with the old ones somewhere after the edit, the rest of the old ones are just moved along.
+    fn reindex(&mut self, from: usize, replaced: Range<usize>, old_end: usize, new_end: usize) {
+        let old = self.starts.split_off(replaced.end);
+        self.starts.truncate(replaced.start);
+        let moved = |old_start: usize| old_start - old_end + new_end;
+        let mut rest = old.len();
+        for (start, _) in print_positions(&self.text[from..]) {
+            let start = from + start;
+            if start >= new_end {
+                if let Ok(i) = old.binary_search_by(|&old_start| moved(old_start).cmp(&start)) {
+                    rest = i;
+                    break;
+                }
+            }
+            self.starts.push(start);
+        }
+        self.starts
+            .extend(old[rest..].iter().map(|&old_start| moved(old_start)));
+    }
+}
+
+impl From<String> for StyledString {
+    fn from(text: String) -> Self {
+        let len = text.len();
+        let mut s = StyledString {
+            text,
+            starts: vec![],
+        };
+        s.reindex(0, 0..0, 0, len);
+        s
+    }
+}
+
+impl From<&str> for StyledString {
+    fn from(text: &str) -> Self {
+        StyledString::from(String::from(text))
+    }
+}
+
+impl From<StyledString> for String {
+    fn from(s: StyledString) -> Self {
+        s.text
+    }
+}
+
+impl fmt::Display for StyledString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
@@ -0,0 +1,176 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::fixtures::Random;
+use crate::print_position_data;
+
+const RED: &str = "\u{1b}[31m";
+const RESET: &str = "\u{1b}[0m";
+
+// the string's index matches a fresh segmentation of its text
+fn check_index(s: &StyledString) {
+    let expected: Vec<_> = print_position_data(s.as_str()).collect();
+    let observed: Vec<_> = (0..s.len()).map(|i| s.get(i).expect("in range")).collect();
+    assert_eq!(expected, observed);
+    assert_eq!(s.get(s.len()), None);
+}
+
+#[test]
+fn accessors() -> Result<()> {
+    let s = StyledString::from(format!("{RED}a\u{4e2d}{RESET}e\u{301}"));
+    assert_eq!(s.len(), 3);
+    assert!(!s.is_empty());
+    assert_eq!(s.get(0), Some("\u{1b}[31ma"));
+    assert_eq!(s.get(1), Some("\u{4e2d}\u{1b}[0m"));
+    assert_eq!(s.byte_range(1..3), 6..16);
+    assert_eq!(s.width(), 4);
+    assert_eq!(s.print_position_info().count(), 3);
+    assert_eq!(s.to_string(), s.as_str());
+    check_index(&s);
+
+    let empty = StyledString::new();
+    assert!(empty.is_empty());
+    assert_eq!(empty.get(0), None);
+    assert_eq!(String::from(empty), "");
+    Ok(())
+}
+
+#[test]
+fn insert() -> Result<()> {
+    let mut s = StyledString::from(format!("a{RED}bc"));
+    // plain text inherits the style where it's inserted
+    s.insert(2, "x");
+    assert_eq!(s.as_str(), format!("a{RED}bxc"));
+    // inserted escapes don't leak into the text after
+    s.insert(1, "\u{1b}[1my");
+    assert_eq!(s.as_str(), format!("a\u{1b}[1my{RESET}{RED}bxc"));
+    s.insert(0, "\u{1b}]8;;http://x.org\u{1b}\\z");
+    assert_eq!(
+        s.as_str(),
+        format!("\u{1b}]8;;http://x.org\u{1b}\\z\u{1b}]8;;\u{1b}\\a\u{1b}[1my{RESET}{RED}bxc")
+    );
+    check_index(&s);
+
+    // the inserted text may combine with its neighbors
+    let mut s = StyledString::from("ab");
+    s.insert(1, "\u{301}");
+    assert_eq!(s.len(), 2);
+    assert_eq!(s.get(0), Some("a\u{301}"));
+    check_index(&s);
+    Ok(())
+}
+
+#[test]
+fn push() -> Result<()> {
+    let mut s = StyledString::new();
+    s.push("a");
+    s.push(RED);
+    s.push("b");
+    assert_eq!(s.as_str(), format!("a{RED}b"));
+    assert_eq!(s.len(), 2);
+    s.push("c");
+    s.push(RESET);
+    assert_eq!(s.as_str(), format!("a{RED}bc{RESET}"));
+    assert_eq!(s.len(), 3);
+
+    // unlike text appended at the end, inserted text doesn't affect what follows
+    s.insert(2, "\u{1b}[1md");
+    assert_eq!(s.as_str(), format!("a{RED}b\u{1b}[1md\u{1b}[0;31mc{RESET}"));
+    check_index(&s);
+    Ok(())
+}
+
+#[test]
+fn remove() -> Result<()> {
+    let content = format!("a{RED}bc{RESET}d");
+    let cases = [
+        (0..1, format!("{RED}bc{RESET}d")),
+        (1..2, format!("a\u{1b}[0;31mc{RESET}d")),
+        (1..3, "ad".to_string()),
+        (2..3, format!("a{RED}b{RESET}d")),
+        (2..4, format!("a{RED}b{RESET}")),
+        (0..4, "".to_string()),
+        (4..4, content.clone()),
+    ];
+    for (range, expected) in cases {
+        let mut s = StyledString::from(content.as_str());
+        s.remove(range.clone());
+        assert_eq!(s.as_str(), expected, "{range:?}");
+        check_index(&s);
+    }
+
+    // removing the escapes which set the style still leaves the following text styled
+    let mut s = StyledString::from(format!("{RED}ab"));
+    s.remove(0..1);
+    assert_eq!(s.as_str(), "\u{1b}[0;31mb");
+    Ok(())
+}
+
+#[test]
+fn replace() -> Result<()> {
+    let mut s = StyledString::from(format!("{RED}abc{RESET}"));
+    s.replace(1..2, "\u{1b}[1mXY");
+    assert_eq!(s.as_str(), format!("{RED}a\u{1b}[1mXY\u{1b}[0;31mc{RESET}"));
+    assert_eq!(s.len(), 4);
+    s.replace(0..4, "plain");
+    assert_eq!(s.as_str(), "plain");
+    check_index(&s);
+    Ok(())
+}
+
+#[test]
+fn random_edits() -> Result<()> {
+    // only the text around each edit is re-segmented, the index must match segmenting all of it
+    let mut random = Random(0xed17);
+    for _ in 0..200 {
+        let pieces = random.below(10);
+        let mut s = StyledString::from(random.text(pieces));
+        for _ in 0..10 {
+            let start = random.below(s.len() + 1);
+            let end = start + random.below(s.len() - start + 1);
+            let pieces = random.below(4);
+            s.replace(start..end, &random.text(pieces));
+            check_index(&s);
+            let pieces = random.below(3);
+            s.push(&random.text(pieces));
+            check_index(&s);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn out_of_bounds() {
+    StyledString::from("ab").remove(1..3);
+}
+
+#[test]
+fn split_at() -> Result<()> {
+    let s = StyledString::from(format!("a{RED}bc{RESET}d"));
+    let cases = [
+        (0, "".to_string(), s.as_str().to_string()),
+        (
+            2,
+            format!("a{RED}b{RESET}"),
+            format!("\u{1b}[0;31mc{RESET}d"),
+        ),
+        (3, format!("a{RED}bc{RESET}"), "d".to_string()),
+        (4, s.as_str().to_string(), "".to_string()),
+    ];
+    for (index, left, right) in cases {
+        let (l, r) = s.split_at(index);
+        assert_eq!(
+            (l.as_str(), r.as_str()),
+            (left.as_str(), right.as_str()),
+            "{index}"
+        );
+        assert_eq!(l.len() + r.len(), s.len());
+        check_index(&l);
+        check_index(&r);
+    }
+    Ok(())
+}
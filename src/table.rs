@@ -0,0 +1,390 @@
+//! Lay out rows of styled text in aligned columns.
+//!
+//! Column widths come from the display width of the cells' print positions, so cells containing
+//! CJK, emoji or SGR escape sequences line up correctly.
+//!
+//! ```rust
+//! use print_positions::table::{Border, Column, Table};
+//! use print_positions::text::Align;
+//!
+//! let mut table = Table::new();
+//! table
+//!     .border(Border::Ascii)
+//!     .column(1, Column { align: Align::Right, ..Column::default() })
+//!     .header(["name", "size"]);
+//! table.row(["\u{1b}[1mREADME\u{1b}[0m", "3"]);
+//! table.row(["\u{65e5}\u{672c}", "120"]);
+//! assert_eq!(
+//!     table.render(),
+//!     concat!(
+//!         "+--------+------+\n",
+//!         "| name   | size |\n",
+//!         "+--------+------+\n",
+//!         "| \u{1b}[1mREADME\u{1b}[0m |    3 |\n",
+//!         "| \u{65e5}\u{672c}   |  120 |\n",
+//!         "+--------+------+\n",
+//!     )
+//! );
+//! ```
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::rendition::Rendition;
+use crate::text::{pad, truncate, wrap, Align};
+use crate::{display_width, print_position_info, PrintPosition};
+
+#[cfg(test)]
+mod tests;
+
+/// What to do with a cell wider than its column's `max_width`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Overflow {
+    /// Wrap the cell onto as many lines as needed.  A column of width 0 truncates instead.
+    #[default]
+    Wrap,
+    /// Cut the cell off at the column width.
+    Truncate,
+}
+
+/// Borders drawn around and between cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Border {
+    /// No borders, columns separated by the table's separator.
+    #[default]
+    None,
+    /// Borders drawn with `+`, `-` and `|`.
+    Ascii,
+    /// Borders drawn with Unicode box drawing characters.
+    Unicode,
+}
+
+/// Layout of one column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Column {
+    /// Placement of cell text within the column.
+    pub align: Align,
+    /// Maximum column width, `None` to fit the widest cell.
+    pub max_width: Option<usize>,
+    /// What to do with cells wider than `max_width`.
+    pub overflow: Overflow,
+}
+
+/// A table of styled text cells.
+#[derive(Clone, Debug)]
+pub struct Table {
+    header: Option<Vec<String>>,
+    rows: Vec<Vec<String>>,
+    columns: Vec<Column>,
+    border: Border,
+    separator: String,
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Table {
+            header: None,
+            rows: vec![],
+            columns: vec![],
+            border: Border::None,
+            separator: "  ".to_string(),
+        }
+    }
+}
+
+// box drawing characters: horizontal, vertical, then corners and junctions
+// for top, middle and bottom rules as (left, middle, right)
+struct Chars {
+    horizontal: char,
+    vertical: char,
+    top: (char, char, char),
+    middle: (char, char, char),
+    bottom: (char, char, char),
+}
+
+const ASCII: Chars = Chars {
+    horizontal: '-',
+    vertical: '|',
+    top: ('+', '+', '+'),
+    middle: ('+', '+', '+'),
+    bottom: ('+', '+', '+'),
+};
+
+const UNICODE: Chars = Chars {
+    horizontal: '\u{2500}',
+    vertical: '\u{2502}',
+    top: ('\u{250c}', '\u{252c}', '\u{2510}'),
+    middle: ('\u{251c}', '\u{253c}', '\u{2524}'),
+    bottom: ('\u{2514}', '\u{2534}', '\u{2518}'),
+};
+
+impl Table {
+    /// An empty table with no borders.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Choose the borders to draw.
+    pub fn border(&mut self, border: Border) -> &mut Self {
+        self.border = border;
+        self
+    }
+
+    /// Choose the text between columns of a table with no borders, two spaces by default.
+    pub fn separator(&mut self, separator: &str) -> &mut Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// Set the layout of column `index` (counting from 0).  Columns not set have the default layout.
+    pub fn column(&mut self, index: usize, column: Column) -> &mut Self {
+        if self.columns.len() <= index {
+            self.columns.resize(index + 1, Column::default());
+        }
+        self.columns[index] = column;
+        self
+    }
+
+    /// Set the header row, which is separated from the other rows by a rule if the table has borders.
+    pub fn header<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) -> &mut Self {
+        self.header = Some(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Append a row.  Rows may have different numbers of cells, missing cells are empty.
+    pub fn row<S: Into<String>>(&mut self, cells: impl IntoIterator<Item = S>) -> &mut Self {
+        self.rows.push(cells.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Lay out the table as lines of text, each ending with a newline.
+    pub fn render(&self) -> String {
+        let all_rows = || self.header.iter().chain(self.rows.iter());
+        let n_columns = all_rows().map(Vec::len).max().unwrap_or(0);
+        let columns: Vec<Column> = (0..n_columns)
+            .map(|i| self.columns.get(i).copied().unwrap_or_default())
+            .collect();
+        let widths: Vec<usize> = columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let widest = all_rows()
+                    .filter_map(|row| row.get(i))
+                    .flat_map(|cell| cell.lines().map(display_width))
+                    .max()
+                    .unwrap_or(0);
+                column.max_width.map_or(widest, |max| widest.min(max))
+            })
+            .collect();
+
+        let chars = match self.border {
+            Border::None => None,
+            Border::Ascii => Some(&ASCII),
+            Border::Unicode => Some(&UNICODE),
+        };
+        let rule = |(left, middle, right): (char, char, char), out: &mut String| {
+            let horizontal = chars.map_or('-', |c| c.horizontal);
+            out.push(left);
+            for (i, width) in widths.iter().enumerate() {
+                if i > 0 {
+                    out.push(middle);
+                }
+                out.extend(core::iter::repeat_n(horizontal, width + 2));
+            }
+            out.push(right);
+            out.push('\n');
+        };
+
+        let mut out = String::new();
+        if let Some(chars) = chars {
+            rule(chars.top, &mut out);
+        }
+        if let Some(header) = &self.header {
+            self.render_row(header, &columns, &widths, chars, &mut out);
+            if let Some(chars) = chars {
+                rule(chars.middle, &mut out);
+            }
+        }
+        for row in &self.rows {
+            self.render_row(row, &columns, &widths, chars, &mut out);
+        }
+        if let Some(chars) = chars {
+            rule(chars.bottom, &mut out);
+        }
+        out
+    }
+
+    fn render_row(
+        &self,
+        row: &[String],
+        columns: &[Column],
+        widths: &[usize],
+        chars: Option<&Chars>,
+        out: &mut String,
+    ) {
+        let cell_lines: Vec<Vec<String>> = columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (column, &width))| {
+                let cell = row.get(i).map_or("", String::as_str);
+                let lines = match column.overflow {
+                    // there's nothing to wrap onto in a column of width 0
+                    Overflow::Wrap if width > 0 => wrap(cell, width),
+                    Overflow::Wrap | Overflow::Truncate => {
+                        cell.lines().map(|l| truncate(l, width)).collect()
+                    }
+                };
+                if lines.is_empty() {
+                    vec![String::new()]
+                } else {
+                    lines
+                }
+            })
+            .collect();
+        let height = cell_lines.iter().map(Vec::len).max().unwrap_or(1);
+
+        for line in 0..height {
+            let mut text = String::new();
+            // without borders, there's no need to show missing cells at the end of the row
+            let n_cells = if chars.is_some() {
+                cell_lines.len()
+            } else {
+                row.len()
+            };
+            for (i, lines) in cell_lines.iter().enumerate().take(n_cells) {
+                let content = lines.get(line).map_or("", String::as_str);
+                match chars {
+                    Some(chars) => {
+                        text.push(chars.vertical);
+                        text.push(' ');
+                        text.push_str(&pad(content, widths[i], columns[i].align));
+                        text.push(' ');
+                    }
+                    None => {
+                        if i > 0 {
+                            text.push_str(&self.separator);
+                        }
+                        text.push_str(&pad(content, widths[i], columns[i].align));
+                    }
+                }
+            }
+            match chars {
+                Some(chars) => text.push(chars.vertical),
+                None => text.truncate(text.trim_end_matches(' ').len()), // no trailing padding
+            }
+            out.push_str(&text);
+            out.push('\n');
+        }
+    }
+}
+
+/// Align the fields of each line of `text` in columns, like `column -t`.
+///
+/// Fields are separated by `delimiter`, or by runs of spaces and tabs if it's `None`,
+/// and are joined with `separator` in the output.  Each output line ends with a newline.
+/// The delimiter is matched against graphemes, never the contents of escape sequences,
+/// and escape sequences in the input are passed through unchanged: any around a delimiter are moved
+/// to the start of the following field.  A style or hyperlink still open at the end of a field
+/// is closed there and reopened at the start of the next field, on the same line or the lines after.
+///
+/// ```rust
+/// use print_positions::table::columnize;
+///
+/// let listing = "\u{1b}[34mdir\u{1b}[0m 4096 today\nfile.txt 12 yesterday\n";
+/// assert_eq!(
+///     columnize(listing, None, "  "),
+///     "\u{1b}[34mdir\u{1b}[0m       4096  today\nfile.txt  12    yesterday\n"
+/// );
+/// assert_eq!(columnize("a,b\nccc,d", Some(","), " | "), "a   | b\nccc | d\n");
+///
+/// // red carries on through the next field and the next line
+/// assert_eq!(
+///     columnize("\u{1b}[31mab c\nd e\u{1b}[0m\n", None, " "),
+///     concat!(
+///         "\u{1b}[31mab\u{1b}[0m \u{1b}[0;31mc\u{1b}[0m\n",
+///         "\u{1b}[0;31md\u{1b}[0m  \u{1b}[0;31me\u{1b}[0m\n"
+///     )
+/// );
+/// ```
+pub fn columnize(text: &str, delimiter: Option<&str>, separator: &str) -> String {
+    let mut table = Table::new();
+    table.separator(separator);
+    let mut rendition = Rendition::default();
+    for line in text.lines() {
+        table.row(split_fields(line, delimiter, &mut rendition));
+    }
+    table.render()
+}
+
+// Split a line into fields, moving escapes found in delimiters to the following field.
+// Each field starts by reopening `rendition`, the style and hyperlink left open before it, which it updates.
+fn split_fields<'a>(
+    line: &'a str,
+    delimiter: Option<&str>,
+    rendition: &mut Rendition<'a>,
+) -> Vec<String> {
+    let positions: Vec<PrintPosition> = print_position_info(line).collect();
+    let mut fields = vec![];
+    let mut field = String::new();
+    let _ = rendition.write_open(&mut field);
+    // length of the escapes reopening the rendition at the start of the field
+    let mut reopen_len = field.len();
+    let mut field_has_grapheme = false;
+    let mut i = 0;
+    while i < positions.len() {
+        let delimiter_len = match delimiter {
+            None => positions[i..]
+                .iter()
+                .take_while(|p| matches!(p.grapheme(), " " | "\t"))
+                .count(),
+            Some(delimiter) => matches_at(&positions[i..], delimiter),
+        };
+        if delimiter_len == 0 {
+            field.push_str(positions[i].as_str());
+            rendition.apply(positions[i].as_str());
+            field_has_grapheme |= positions[i].has_grapheme();
+            i += 1;
+            continue;
+        }
+        // runs of whitespace at the start of the line don't delimit an (empty) field
+        if delimiter.is_some() || field_has_grapheme {
+            fields.push(core::mem::take(&mut field));
+            let _ = rendition.write_open(&mut field);
+            reopen_len = field.len();
+            field_has_grapheme = false;
+        }
+        for p in &positions[i..i + delimiter_len] {
+            field.push_str(p.prefix_escapes());
+            field.push_str(p.suffix_escapes());
+            rendition.apply(p.as_str());
+        }
+        i += delimiter_len;
+    }
+    if delimiter.is_some() || field_has_grapheme || fields.is_empty() {
+        fields.push(field);
+    } else if let Some(last) = fields.last_mut() {
+        last.push_str(&field[reopen_len..]); // trailing whitespace: keep any escapes it contained
+    }
+    fields
+}
+
+// number of print positions at the start of `positions` whose graphemes spell out `delimiter`, or 0
+fn matches_at(positions: &[PrintPosition], delimiter: &str) -> usize {
+    let mut rest = delimiter;
+    for (n, p) in positions.iter().enumerate() {
+        if rest.is_empty() {
+            return n;
+        }
+        match rest.strip_prefix(p.grapheme()) {
+            Some(r) if p.has_grapheme() => rest = r,
+            _ => return 0,
+        }
+    }
+    if rest.is_empty() {
+        positions.len()
+    } else {
+        0
+    }
+}
@@ -0,0 +1,142 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[test]
+fn no_border_layout() -> Result<()> {
+    let mut table = Table::new();
+    table.row(["a", "bb", "c"]);
+    table.row(["\u{1f600}\u{1f600}", "\x1b[32mx\x1b[0m"]);
+    assert_eq!(
+        table.render(),
+        concat!("a     bb  c\n", "\u{1f600}\u{1f600}  \x1b[32mx\x1b[0m\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn unicode_border_and_wrap() -> Result<()> {
+    let mut table = Table::new();
+    table.border(Border::Unicode).column(
+        0,
+        Column {
+            max_width: Some(4),
+            ..Column::default()
+        },
+    );
+    table.column(
+        1,
+        Column {
+            max_width: Some(3),
+            overflow: Overflow::Truncate,
+            align: Align::Center,
+        },
+    );
+    table.header(["key", "value"]);
+    table.row(["abcdefg", "x"]);
+    assert_eq!(
+        table.render(),
+        concat!(
+            "┌──────┬─────┐\n",
+            "│ key  │ val │\n",
+            "├──────┼─────┤\n",
+            "│ abcd │  x  │\n",
+            "│ efg  │     │\n",
+            "└──────┴─────┘\n",
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn wrapped_styles_stay_in_their_cells() -> Result<()> {
+    let mut table = Table::new();
+    table.separator("|").column(
+        0,
+        Column {
+            max_width: Some(2),
+            ..Column::default()
+        },
+    );
+    table.row(["\x1b[31mabc", "z"]);
+    assert_eq!(
+        table.render(),
+        concat!("\x1b[31mab\x1b[0m|z\n", "\x1b[0;31mc\x1b[0m |\n")
+    );
+    Ok(())
+}
+
+#[test]
+fn empty_and_multiline_cells() -> Result<()> {
+    assert_eq!(Table::new().render(), "");
+    let mut table = Table::new();
+    table.border(Border::Ascii).row(["one\ntwo", ""]);
+    assert_eq!(
+        table.render(),
+        concat!(
+            "+-----+--+\n",
+            "| one |  |\n",
+            "| two |  |\n",
+            "+-----+--+\n",
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn columnize_whitespace() -> Result<()> {
+    let input = "  a  b\t\tc  \n\nddd e\x1b[1m f\x1b[0m\n";
+    assert_eq!(
+        columnize(input, None, " "),
+        "a   b c\n\nddd e \x1b[1mf\x1b[0m\n"
+    );
+    // escapes in an OSC parameter aren't split, even though they contain spaces
+    let input = "\x1b]0;a title\x07x y";
+    assert_eq!(columnize(input, None, "|"), "\x1b]0;a title\x07x|y\n");
+    Ok(())
+}
+
+#[test]
+fn columnize_delimiter() -> Result<()> {
+    assert_eq!(
+        columnize("a::b::\n\u{4e2d}::c", Some("::"), "|"),
+        "a |b|\n\u{4e2d}|c\n"
+    );
+    // delimiter doesn't match within a grapheme
+    assert_eq!(columnize("e\u{301},x", Some("e"), "|"), "e\u{301},x\n");
+    Ok(())
+}
+
+#[test]
+fn columnize_carries_style_across_lines() -> Result<()> {
+    let input = "\x1b]8;;http://x\x1b\\\x1b[1ma b \nc\x1b[0m d\x1b]8;;\x1b\\\ne";
+    assert_eq!(
+        columnize(input, None, "|"),
+        concat!(
+            "\x1b]8;;http://x\x1b\\\x1b[1ma\x1b]8;;\x1b\\\x1b[0m|",
+            "\x1b[0;1m\x1b]8;;http://x\x1b\\b\x1b]8;;\x1b\\\x1b[0m\n",
+            "\x1b[0;1m\x1b]8;;http://x\x1b\\c\x1b[0m\x1b]8;;\x1b\\|",
+            "\x1b]8;;http://x\x1b\\d\x1b]8;;\x1b\\\n",
+            "e\n"
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn wrap_at_zero_width_truncates() -> Result<()> {
+    let mut table = Table::new();
+    table.separator("|").column(
+        0,
+        Column {
+            max_width: Some(0),
+            ..Column::default()
+        },
+    );
+    table.row(["abc", "x"]);
+    table.row(["\x1b[1m", "yz"]);
+    assert_eq!(table.render(), concat!("|x\n", "\x1b[1m\x1b[0m|yz\n"));
+    Ok(())
+}
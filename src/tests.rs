@@ -4,6 +4,8 @@ use super::*;
 #[allow(unused_imports)]
 use anyhow::{anyhow, Context, Result};
 
+use crate::fixtures::Random;
+
 fn esc_sgr_reset0() -> &'static str {
     "\x1b[0m"
 }
@@ -98,11 +100,38 @@ fn embedded_csi_and_trailing_reset() -> Result<()> {
 #[test]
 fn non_reset_esc_seq_at_end_of_string() -> Result<()> {
     let test_input = ["abc", "\u{1b}\x06"]; // garbage esc seq at end of string
-    let expect = vec![(0, "a"), (1, "b"), (2, "c"), (3, "\u{1b}\x06")];
+    let expect = vec![(0, "a"), (1, "b"), (2, "c\u{1b}\x06")];
 
     run_test("", &expect, &test_input)
 }
 
+#[test]
+fn trailing_escapes_attach_to_last_grapheme() -> Result<()> {
+    let test_input = ["ab", esc_sgr_color(), "c", "\x1b[31m", "\x1b]0;title\x07"];
+    let e1 = [esc_sgr_color(), "c", "\x1b[31m", "\x1b]0;title\x07"].join("");
+    let expect = vec![(0, "a"), (1, "b"), (2, &e1)];
+
+    run_test("", &expect, &test_input)?;
+    assert!(print_position_data(&test_input.join("")).all(has_grapheme));
+    Ok(())
+}
+
+#[test]
+fn escapes_only() -> Result<()> {
+    let test_input = [esc_sgr_color(), esc_sgr_reset()];
+    let e1 = test_input.join("");
+    let expect = vec![(0, e1.as_str())];
+
+    run_test("", &expect, &test_input)?;
+    assert!(!has_grapheme(&e1));
+    assert!(!has_grapheme(""));
+    assert!(
+        has_grapheme("\n"),
+        "control chars are graphemes, though not visible"
+    );
+    Ok(())
+}
+
 #[test]
 fn double_trailing_reset() -> Result<()> {
     let test_input = [
@@ -225,3 +254,216 @@ fn fuzz_failure_1() -> Result<()> {
 
     run_test("", &expected, &input)
 }
+
+#[test]
+fn print_position_parts() -> Result<()> {
+    let input = [
+        "a",
+        esc_sgr_color(),
+        "e\u{301}",
+        esc_sgr_reset0(),
+        "\t",
+        "\u{ff21}",
+        "\x1b[31m",
+    ];
+    let content = input.join("");
+    let observed: Vec<_> = print_position_info(&content)
+        .map(|p| {
+            (
+                p.prefix_escapes(),
+                p.grapheme(),
+                p.suffix_escapes(),
+                p.width(),
+                p.is_control(),
+            )
+        })
+        .collect();
+    let expected = vec![
+        ("", "a", "", 1, false),
+        (esc_sgr_color(), "e\u{301}", esc_sgr_reset0(), 1, false),
+        ("", "\t", "", 0, true),
+        ("", "\u{ff21}", "\x1b[31m", 2, false),
+    ];
+    assert_eq!(expected, observed);
+
+    // same segmentation as the other iterators
+    let ranges: Vec<_> = print_position_info(&content).map(|p| p.range()).collect();
+    let offsets: Vec<_> = print_positions(&content).map(|(s, e)| s..e).collect();
+    assert_eq!(offsets, ranges);
+    for p in print_position_info(&content) {
+        assert_eq!(&content[p.range()], p.as_str());
+        assert_eq!(
+            p.as_str(),
+            [p.prefix_escapes(), p.grapheme(), p.suffix_escapes()].join("")
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn print_position_escapes_only() -> Result<()> {
+    let content = [esc_sgr_color(), esc_sgr_reset()].join("");
+    let observed: Vec<_> = print_position_info(&content).collect();
+    assert_eq!(observed.len(), 1);
+    assert_eq!(observed[0].prefix_escapes(), content);
+    assert_eq!(observed[0].grapheme(), "");
+    assert!(!observed[0].has_grapheme());
+    assert_eq!(observed[0].width(), 0);
+    Ok(())
+}
+
+#[test]
+fn zero_width_graphemes() -> Result<()> {
+    let segmenter = Segmenter::new();
+    for g in [
+        "\u{200d}", "\u{ad}", "\u{200b}", "\u{feff}", "\u{200e}", "\u{200f}", "\u{301}",
+    ] {
+        let positions: Vec<_> = print_position_info(g).collect();
+        assert_eq!(positions.len(), 1, "{g:?} alone is a print position");
+        assert_eq!(positions[0].width(), 0, "{g:?}");
+        assert!(positions[0].is_zero_width(), "{g:?}");
+        assert!(segmenter.is_zero_width(g), "{g:?}");
+    }
+    assert!(!segmenter.is_zero_width("\t"));
+    assert!(!segmenter.is_zero_width(""));
+    assert!(!segmenter.is_zero_width("a"));
+    Ok(())
+}
+
+#[test]
+fn attach_zero_width() -> Result<()> {
+    let segmenter = Segmenter::new().attach_zero_width(true);
+    let cases = [
+        ("leading combining mark", "\u{301}ab", vec!["\u{301}a", "b"]),
+        ("between", "a\u{200b}b", vec!["a", "\u{200b}b"]),
+        (
+            "with escapes",
+            "a\u{1b}[1m\u{200e}\u{1b}[31mb",
+            vec!["a", "\u{1b}[1m\u{200e}\u{1b}[31mb"],
+        ),
+        (
+            "at end, after reset",
+            "ab\u{1b}[0m\u{ad}\u{1b}[31m\u{feff}",
+            vec!["a", "b\u{1b}[0m\u{ad}\u{1b}[31m\u{feff}"],
+        ),
+        (
+            "only zero width",
+            "\u{200b}\u{200d}",
+            vec!["\u{200b}\u{200d}"],
+        ),
+        ("controls are kept", "a\u{ad}\n", vec!["a", "\u{ad}\n"]),
+    ];
+    for (tag, input, expected) in cases {
+        let observed: Vec<_> = segmenter
+            .print_positions(input)
+            .map(|(start, end)| &input[start..end])
+            .collect();
+        assert_eq!(expected, observed, "{tag}");
+
+        let info: Vec<_> = segmenter.print_position_info(input).collect();
+        let ranges: Vec<_> = info.iter().map(|p| p.range()).collect();
+        let offsets: Vec<_> = segmenter
+            .print_positions(input)
+            .map(|(s, e)| s..e)
+            .collect();
+        assert_eq!(offsets, ranges, "{tag}");
+        assert!(
+            info.iter().all(|p| !p.is_zero_width()),
+            "{tag}: no zero width graphemes left"
+        );
+        assert_eq!(
+            info.iter().map(|p| p.width()).sum::<usize>(),
+            display_width(input),
+            "{tag}: width unchanged"
+        );
+    }
+
+    let info: Vec<_> = segmenter.print_position_info("\u{200b}\u{200d}").collect();
+    assert!(!info[0].has_grapheme());
+    assert_eq!(info[0].prefix_escapes(), "\u{200b}\u{200d}");
+    Ok(())
+}
+
+#[test]
+fn attached_graphemes_among_escapes() -> Result<()> {
+    // the graphemes attached among the escape sequences don't change what the escape sequences do
+    // (Screen and ColumnTracker segment without attaching, so they never see any)
+    let input = "\u{1b}[1m\u{200e}\u{1b}[31mb\u{1b}[0m\u{ad}\u{1b}[4m\u{feff}\nc";
+    let attached = Segmenter::new().attach_zero_width(true);
+    let p = attached
+        .print_position_info(input)
+        .next()
+        .expect("a print position");
+    assert_eq!(p.prefix_escapes(), "\u{1b}[1m\u{200e}\u{1b}[31m");
+    assert_eq!(p.grapheme(), "b");
+    assert_eq!(p.suffix_escapes(), "\u{1b}[0m");
+
+    let style = |segmenter: Segmenter| {
+        let mut style = style::Style::default();
+        for p in segmenter.print_position_info(input) {
+            style.apply_escapes(p.prefix_escapes());
+            style.apply_escapes(p.suffix_escapes());
+        }
+        style
+    };
+    assert_eq!(style(attached), style(Segmenter::new()));
+    assert!(style(attached).underline);
+
+    let line_styles = |segmenter: Segmenter| -> Vec<style::Style> {
+        segmenter.lines(input).map(|line| line.style()).collect()
+    };
+    assert_eq!(line_styles(attached), line_styles(Segmenter::new()));
+    Ok(())
+}
+
+// segment the slow way, every grapheme from the segmenter, to check the ASCII fast path against
+fn reference_positions(s: &str) -> Vec<(usize, usize)> {
+    let graphemes: Vec<_> = UnicodeSegmentation::grapheme_indices(s, true).collect();
+    let mut positions = vec![];
+    let (mut start, mut i) = (0, 0);
+    while i < graphemes.len() {
+        let mut state = EscapeState::Normal;
+        while i < graphemes.len() {
+            let grapheme = graphemes[i].1;
+            i += 1;
+            match state.advance(grapheme.as_bytes()[0]) {
+                Some(next) => state = next,
+                None => break,
+            }
+        }
+        let mut end = graphemes.get(i).map_or(s.len(), |g| g.0);
+        // take any reset sequences, and the rest of the grapheme each ends in
+        while let Some(reset) = ["\u{1b}c", "\u{1b}[m", "\u{1b}[0m"]
+            .iter()
+            .find(|reset| s[end..].starts_with(*reset))
+        {
+            let reset_end = end + reset.len();
+            while graphemes[i].0 < reset_end {
+                i += 1;
+                end = graphemes.get(i).map_or(s.len(), |g| g.0);
+                if end == s.len() {
+                    break;
+                }
+            }
+        }
+        if !has_grapheme(&s[end..]) {
+            end = s.len();
+            i = graphemes.len();
+        }
+        positions.push((start, end));
+        start = end;
+    }
+    positions
+}
+
+#[test]
+fn ascii_fast_path() -> Result<()> {
+    let mut random = Random(0x5eed);
+    for _ in 0..2000 {
+        let pieces = random.below(12);
+        let s = random.text(pieces);
+        let positions: Vec<_> = print_positions(&s).collect();
+        assert_eq!(positions, reference_positions(&s), "{s:?}");
+    }
+    Ok(())
+}
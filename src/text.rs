@@ -0,0 +1,189 @@
+//! Operations on text which respect print position boundaries: cutting it into pieces, padding and stripping escapes.
+//!
+//! A piece cut out of styled text doesn't render correctly on its own if it depends on escape sequences
+//! that were cut off: it could lose its color, or leave a color or an OSC 8 hyperlink open, bleeding into whatever follows it.
+//! So the functions here start each piece by reopening the style and hyperlink in effect where it was cut,
+//! and end it by closing whatever is still open.
+//!
+//! ```rust
+//! use print_positions::text::{slice, truncate, wrap};
+//!
+//! let content = "\u{1b}[31mred\u{1b}[0m plain";
+//! assert_eq!(slice(content, 1..3), "\u{1b}[0;31med\u{1b}[0m");
+//! assert_eq!(truncate(content, 2), "\u{1b}[31mre\u{1b}[0m");
+//! assert_eq!(wrap(content, 5), vec!["\u{1b}[31mred\u{1b}[0m p", "lain"]);
+//! ```
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::rendition::Rendition;
+use crate::{display_width, print_position_info, PrintPosition};
+
+#[cfg(test)]
+mod tests;
+
+/// Copy print positions `range.start` up to (but not including) `range.end` from `s`.
+///
+/// The copy starts with escape sequences which reopen the style and hyperlink in effect
+/// at `range.start` and ends by closing anything still open at `range.end`.
+/// A range extending past the end of `s` is cut short.
+pub fn slice(s: &str, range: Range<usize>) -> String {
+    let mut piece = Piece::default();
+    for (i, p) in print_position_info(s).enumerate() {
+        if i >= range.end {
+            break;
+        }
+        piece.push(p, i >= range.start);
+    }
+    piece.finish()
+}
+
+/// Copy as many leading print positions of `s` as fit in `width` columns.
+///
+/// Width is the display width of each print position (see [PrintPosition::width]),
+/// so a double-width character which would straddle the limit is left out.
+/// Any style or hyperlink still open at the cut is closed.
+pub fn truncate(s: &str, width: usize) -> String {
+    let mut piece = Piece::default();
+    let mut used = 0;
+    for p in print_position_info(s) {
+        used += p.width();
+        if used > width {
+            break;
+        }
+        piece.push(p, true);
+    }
+    piece.finish()
+}
+
+/// Break `s` into lines no wider than `width` columns.
+///
+/// Lines break at newlines in `s` (which are not included in the lines) and wherever the next print position would
+/// not fit in `width`.  Each line reopens the style and hyperlink in effect at its start and closes anything
+/// still open at its end, so lines can be printed separately or rearranged.
+/// A print position wider than `width` gets a line of its own, and a newline at the end of `s` is followed by an empty line.
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut piece = Piece::default();
+    let mut used = 0;
+    for p in print_position_info(s) {
+        let grapheme = p.grapheme();
+        if grapheme == "\n" || grapheme == "\r\n" {
+            piece.push_escapes(p);
+            lines.push(piece.take());
+            used = 0;
+            continue;
+        }
+        let p_width = p.width();
+        if used + p_width > width && used > 0 {
+            lines.push(piece.take());
+            used = 0;
+        }
+        used += p_width;
+        piece.push(p, true);
+    }
+    if piece.started || !lines.is_empty() {
+        lines.push(piece.finish());
+    }
+    lines
+}
+
+/// Placement of text within a wider field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Align {
+    /// Text at the left, padding on the right.
+    #[default]
+    Left,
+    /// Text at the right, padding on the left.
+    Right,
+    /// Text centered, with any odd column of padding on the right.
+    Center,
+}
+
+/// Pad `s` with spaces to fill `width` columns.
+///
+/// Text already `width` columns or wider is returned unchanged.
+///
+/// ```rust
+/// use print_positions::text::{pad, Align};
+///
+/// assert_eq!(pad("\u{1b}[1mab\u{1b}[0m", 5, Align::Right), "   \u{1b}[1mab\u{1b}[0m");
+/// assert_eq!(pad("\u{4e2d}", 5, Align::Center), " \u{4e2d}  ");
+/// ```
+pub fn pad(s: &str, width: usize, align: Align) -> String {
+    let padding = width.saturating_sub(display_width(s));
+    let left = match align {
+        Align::Left => 0,
+        Align::Right => padding,
+        Align::Center => padding / 2,
+    };
+    let mut padded = String::with_capacity(s.len() + padding);
+    padded.extend(core::iter::repeat_n(' ', left));
+    padded.push_str(s);
+    padded.extend(core::iter::repeat_n(' ', padding - left));
+    padded
+}
+
+/// Copy `s` without its escape sequences, leaving just the graphemes.
+///
+/// ```rust
+/// use print_positions::text::strip_escapes;
+///
+/// assert_eq!(strip_escapes("\u{1b}[1;31merror:\u{1b}[0m \u{1b}]8;;http://x\u{7}link\u{1b}]8;;\u{7}"), "error: link");
+/// ```
+pub fn strip_escapes(s: &str) -> String {
+    print_position_info(s).map(|p| p.grapheme()).collect()
+}
+
+// A piece of text being copied out of a source string.
+#[derive(Default)]
+struct Piece<'a> {
+    text: String,
+    // rendition state at end of text, including the print positions skipped before the piece started
+    rendition: Rendition<'a>,
+    started: bool,
+}
+
+impl<'a> Piece<'a> {
+    // append `p` to the piece if `keep`, otherwise just track its escapes.
+    fn push(&mut self, p: PrintPosition<'a>, keep: bool) {
+        if keep {
+            if !self.started {
+                let _ = self.rendition.write_open(&mut self.text);
+                self.started = true;
+            }
+            self.text.push_str(p.as_str());
+        }
+        self.rendition.apply(p.prefix_escapes());
+        self.rendition.apply(p.suffix_escapes());
+    }
+
+    // append the escapes of `p` but not its grapheme
+    fn push_escapes(&mut self, p: PrintPosition<'a>) {
+        if !self.started {
+            let _ = self.rendition.write_open(&mut self.text);
+            self.started = true;
+        }
+        self.text.push_str(p.prefix_escapes());
+        self.text.push_str(p.suffix_escapes());
+        self.rendition.apply(p.prefix_escapes());
+        self.rendition.apply(p.suffix_escapes());
+    }
+
+    // finish this piece, and set up to start the next one where this one left off
+    fn take(&mut self) -> String {
+        let _ = self.rendition.write_close(&mut self.text);
+        self.started = false;
+        core::mem::take(&mut self.text)
+    }
+
+    fn finish(mut self) -> String {
+        if self.started {
+            let _ = self.rendition.write_close(&mut self.text);
+        }
+        self.text
+    }
+}
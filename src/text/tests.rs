@@ -0,0 +1,81 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+const LINK: &str = "\x1b]8;;http://x\x1b\\";
+const LINK_CLOSE: &str = "\x1b]8;;\x1b\\";
+
+#[test]
+fn slice_plain_text() -> Result<()> {
+    assert_eq!(slice("abcdef", 1..4), "bcd");
+    assert_eq!(slice("abcdef", 4..10), "ef");
+    assert_eq!(slice("abcdef", 7..10), "");
+    assert_eq!(slice("a\u{1f600}b", 1..2), "\u{1f600}");
+    Ok(())
+}
+
+#[test]
+fn slice_reopens_style_and_link() -> Result<()> {
+    let content = ["a\x1b[1m", LINK, "bcd", LINK_CLOSE, "e\x1b[0m"].join("");
+    assert_eq!(
+        slice(&content, 2..3),
+        ["\x1b[0;1m", LINK, "c", LINK_CLOSE, "\x1b[0m"].join("")
+    );
+    // escapes within the slice are kept, and tracked
+    assert_eq!(
+        slice(&content, 3..5),
+        ["\x1b[0;1m", LINK, "d", LINK_CLOSE, "e\x1b[0m"].join("")
+    );
+    Ok(())
+}
+
+#[test]
+fn truncate_by_width() -> Result<()> {
+    assert_eq!(truncate("abc", 5), "abc");
+    assert_eq!(truncate("a\u{4e2d}b", 2), "a", "wide char doesn't fit");
+    assert_eq!(truncate("a\u{4e2d}b", 3), "a\u{4e2d}");
+    let content = [LINK, "link", LINK_CLOSE].join("");
+    assert_eq!(truncate(&content, 2), [LINK, "li", LINK_CLOSE].join(""));
+    assert_eq!(truncate(&content, 0), "");
+    Ok(())
+}
+
+#[test]
+fn wrap_lines() -> Result<()> {
+    assert_eq!(wrap("abcdefg", 3), vec!["abc", "def", "g"]);
+    assert_eq!(wrap("ab\ncd\n", 3), vec!["ab", "cd", ""]);
+    assert_eq!(wrap("\u{4e2d}\u{4e2d}", 3), vec!["\u{4e2d}", "\u{4e2d}"]);
+    assert_eq!(wrap("", 3), Vec::<String>::new());
+
+    let content = ["\x1b[32m", LINK, "abcd", LINK_CLOSE, "\x1b[0m"].join("");
+    assert_eq!(
+        wrap(&content, 3),
+        vec![
+            ["\x1b[32m", LINK, "abc", LINK_CLOSE, "\x1b[0m"].join(""),
+            ["\x1b[0;32m", LINK, "d", LINK_CLOSE, "\x1b[0m"].join(""),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn hyperlink_per_position() -> Result<()> {
+    let content = ["a", LINK, "b", "\x1bc", "c"].join("");
+    let links: Vec<_> = print_position_info(&content)
+        .map(|p| p.hyperlink().map(|l| l.uri()))
+        .collect();
+    assert_eq!(links, vec![None, Some("http://x"), None]);
+    Ok(())
+}
+
+#[test]
+fn pad_and_strip() -> Result<()> {
+    assert_eq!(pad("ab", 4, Align::Left), "ab  ");
+    assert_eq!(pad("ab", 5, Align::Center), " ab  ");
+    assert_eq!(pad("abcdef", 4, Align::Right), "abcdef");
+    assert_eq!(strip_escapes("plain"), "plain");
+    assert_eq!(strip_escapes("\x1b[31m"), "");
+    Ok(())
+}
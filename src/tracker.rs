@@ -0,0 +1,259 @@
+//! Keep track of the output column while writing text piece by piece.
+//!
+//! ```rust
+//! use core::fmt::Write;
+//! use print_positions::tracker::ColumnTracker;
+//!
+//! let mut out = ColumnTracker::new(String::new());
+//! write!(out, "\u{1b}[1mtotal:\u{1b}[0m").unwrap();
+//! assert_eq!(out.column(), 6);
+//! // pad to column 10
+//! let padding = 10 - out.column();
+//! write!(out, "{:padding$}{}", "", 42).unwrap();
+//! assert_eq!(out.column(), 12);
+//! assert_eq!(out.into_inner(), "\u{1b}[1mtotal:\u{1b}[0m    42");
+//! ```
+
+use alloc::string::String;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::style::Style;
+use crate::{print_position_info, EscapeState, PrintPosition, TAB_STOP};
+
+#[cfg(test)]
+mod tests;
+
+/// A writer which passes everything written to it through to an inner writer, while keeping track of
+/// the cursor column, the print positions written since the last newline and the current [Style].
+///
+/// It implements [fmt::Write] if the inner writer does, and [io::Write] if the inner writer does.
+/// Escape sequences, graphemes and (for [io::Write]) UTF-8 characters may be split across writes:
+/// the tracker holds on to the last, possibly incomplete, print position and finishes it when more
+/// text arrives.  Queries treat it as if the text written so far were all there is.
+///
+/// Control characters CR, LF, TAB (stops every 8 columns) and BS move the column,
+/// other control characters and escape sequences (including cursor motion sequences) don't.
+#[derive(Clone, Debug)]
+pub struct ColumnTracker<W> {
+    inner: W,
+    // state as of the start of `pending`
+    settled: State,
+    // text not yet known to be a complete print position
+    pending: String,
+    // state as of the end of `pending`
+    current: State,
+    // how far the escape sequences ending `pending` have been scanned, if it ends in escape sequences
+    tail: Option<Tail>,
+    // bytes of an incomplete UTF-8 char (io::Write only)
+    #[cfg(feature = "std")]
+    partial_char: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct State {
+    column: usize,
+    positions: usize,
+    style: Style,
+}
+
+impl State {
+    fn advance(&mut self, p: &PrintPosition) {
+        self.style.apply_escapes(p.prefix_escapes());
+        match p.grapheme() {
+            "\n" | "\r\n" => {
+                self.column = 0;
+                self.positions = 0;
+            }
+            "\r" => self.column = 0,
+            "\t" => self.column = (self.column / TAB_STOP + 1) * TAB_STOP,
+            "\x08" => self.column = self.column.saturating_sub(1),
+            _ if p.is_control() || !p.has_grapheme() => {}
+            _ => {
+                self.column += p.width();
+                self.positions += 1;
+            }
+        }
+        self.style.apply_escapes(p.suffix_escapes());
+    }
+}
+
+// The escape sequences ending the pending print position, scanned so far.  While text written is more escape sequences
+// (say, an unterminated OSC sequence), scanning resumes here and the pending print position isn't segmented again.
+#[derive(Clone, Copy, Debug)]
+struct Tail {
+    // offset in `pending` of the last grapheme scanned, which may combine with what's written next, and the state before it
+    last: usize,
+    state: EscapeState,
+    // offset in `pending` of the escape sequence still open at `last`
+    sequence: usize,
+}
+
+// Scan `pending` from `tail` on, calling `complete` with the range of each escape sequence completed.
+// `None` if a grapheme which isn't part of an escape sequence turns up.
+fn scan_tail(
+    pending: &str,
+    mut tail: Tail,
+    mut complete: impl FnMut(Range<usize>),
+) -> Option<Tail> {
+    let from = tail.last;
+    let mut state = tail.state;
+    for (offset, grapheme) in pending[from..].grapheme_indices(true) {
+        let offset = from + offset;
+        if state == EscapeState::Normal {
+            tail.sequence = offset;
+        }
+        tail.last = offset;
+        tail.state = state;
+        state = state.advance(grapheme.as_bytes()[0])?;
+        if state == EscapeState::Normal {
+            complete(tail.sequence..offset + grapheme.len());
+        }
+    }
+    Some(tail)
+}
+
+impl<W> ColumnTracker<W> {
+    /// Wrap `inner`, with the cursor assumed to be at the start of a line, in the default style.
+    pub fn new(inner: W) -> Self {
+        ColumnTracker {
+            inner,
+            settled: State::default(),
+            pending: String::new(),
+            current: State::default(),
+            tail: None,
+            #[cfg(feature = "std")]
+            partial_char: Vec::new(),
+        }
+    }
+
+    /// Current cursor column, counting from 0.
+    pub fn column(&self) -> usize {
+        self.current.column
+    }
+
+    /// Number of print positions written since the last newline.
+    pub fn positions(&self) -> usize {
+        self.current.positions
+    }
+
+    /// The style selected by the SGR escape sequences written so far.
+    pub fn style(&self) -> Style {
+        self.current.style
+    }
+
+    /// The inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// The inner writer.  Writing to it directly throws off the tracking.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwrap the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    // track text which has been written to the inner writer
+    fn track(&mut self, s: &str) {
+        self.pending.push_str(s);
+        if let Some(tail) = self.tail {
+            let (pending, style) = (&self.pending, &mut self.current.style);
+            self.tail = scan_tail(pending, tail, |escapes| {
+                style.apply_escapes(&pending[escapes])
+            });
+            if self.tail.is_some() {
+                // still escape sequences, the pending print position just got longer
+                return;
+            }
+        }
+
+        let mut positions = print_position_info(&self.pending).peekable();
+        let mut settled_len = 0;
+        let mut current = self.settled;
+        let mut escapes = 0;
+        while let Some(p) = positions.next() {
+            if positions.peek().is_none() {
+                // the last one may not be complete yet
+                current.advance(&p);
+                escapes = if p.has_grapheme() {
+                    p.range().end - p.suffix_escapes().len()
+                } else {
+                    p.range().start
+                };
+                break;
+            }
+            self.settled.advance(&p);
+            current = self.settled;
+            settled_len = p.range().end;
+        }
+        self.pending.drain(..settled_len);
+        self.current = current;
+        let escapes = escapes.saturating_sub(settled_len);
+        let tail = Tail {
+            last: escapes,
+            state: EscapeState::Normal,
+            sequence: escapes,
+        };
+        // `current` already reflects the escape sequences there
+        self.tail = scan_tail(&self.pending, tail, |_| {});
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for ColumnTracker<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_str(s)?;
+        self.track(s);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> io::Write for ColumnTracker<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.partial_char.extend_from_slice(&buf[..written]);
+        let mut bytes = core::mem::take(&mut self.partial_char);
+        let mut rest = bytes.as_slice();
+        loop {
+            match core::str::from_utf8(rest) {
+                Ok(s) => {
+                    self.track(s);
+                    break;
+                }
+                Err(err) => {
+                    let (valid, after) = rest.split_at(err.valid_up_to());
+                    self.track(core::str::from_utf8(valid).expect("checked valid"));
+                    match err.error_len() {
+                        // invalid bytes, which a terminal would show as a replacement character
+                        Some(len) => {
+                            self.track("\u{fffd}");
+                            rest = &after[len..];
+                        }
+                        None => {
+                            // incomplete char, wait for the rest of it
+                            let keep = after.len();
+                            bytes.drain(..bytes.len() - keep);
+                            self.partial_char = bytes;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,128 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+use crate::fixtures::Random;
+use crate::style::Color;
+use core::fmt::Write as _;
+#[cfg(feature = "std")]
+use std::io::Write as _;
+
+#[test]
+fn columns_and_positions() -> Result<()> {
+    let mut out = ColumnTracker::new(String::new());
+    write!(out, "ab\u{4e2d}")?;
+    assert_eq!((out.column(), out.positions()), (4, 3));
+    write!(out, "\tx")?;
+    assert_eq!((out.column(), out.positions()), (9, 4));
+    write!(out, "\rz\x08")?;
+    assert_eq!((out.column(), out.positions()), (0, 5));
+    write!(out, "yz\nw")?;
+    assert_eq!((out.column(), out.positions()), (1, 1));
+    assert_eq!(out.get_ref(), "ab\u{4e2d}\tx\rz\x08yz\nw");
+    Ok(())
+}
+
+#[test]
+fn split_escapes_and_graphemes() -> Result<()> {
+    let mut out = ColumnTracker::new(String::new());
+    for piece in [
+        "a\x1b", "[3", "1mb", "e", "\u{301}", "\u{301}", "\x1b[0", "m",
+    ] {
+        write!(out, "{piece}")?;
+    }
+    assert_eq!((out.column(), out.positions()), (3, 3));
+    assert!(out.style().is_default());
+
+    let mut out = ColumnTracker::new(String::new());
+    write!(out, "\x1b[38;5")?;
+    assert_eq!(out.style().foreground, None);
+    write!(out, ";200mx")?;
+    assert_eq!(out.style().foreground, Some(Color::Indexed(200)));
+    assert_eq!(out.column(), 1);
+    Ok(())
+}
+
+#[test]
+fn long_escape_runs() -> Result<()> {
+    // an unterminated OSC sequence written piece by piece stays one print position
+    let mut out = ColumnTracker::new(String::new());
+    write!(out, "a\x1b[1m\x1b]0;")?;
+    for _ in 0..1000 {
+        write!(out, "no terminator ")?;
+        assert_eq!((out.column(), out.positions()), (1, 1));
+    }
+    assert!(out.style().bold);
+    write!(out, "\x07\x1b[31mb")?;
+    assert_eq!((out.column(), out.positions()), (2, 2));
+    assert_eq!(out.style().foreground, Some(Color::Indexed(1)));
+
+    // a run of escape sequences, split anywhere, changes the style as each one is completed
+    let mut out = ColumnTracker::new(String::new());
+    write!(out, "a")?;
+    for _ in 0..100 {
+        write!(out, "\x1b[1m\x1b[3")?;
+        assert!(out.style().bold);
+        assert_eq!(out.style().foreground, None);
+        write!(out, "2m\x1b[")?;
+        assert_eq!(out.style().foreground, Some(Color::Indexed(2)));
+        write!(out, "0m")?;
+        assert!(out.style().is_default());
+    }
+    write!(out, "\x1b\r")?;
+    write!(out, "\nb")?;
+    let mut expected = ColumnTracker::new(String::new());
+    write!(expected, "{}", out.get_ref())?;
+    assert_eq!(
+        (out.column(), out.positions(), out.style()),
+        (expected.column(), expected.positions(), expected.style())
+    );
+    Ok(())
+}
+
+#[test]
+fn random_splits() -> Result<()> {
+    // however the text is split up, the tracker ends up where it does when it's written at once
+    let mut random = Random(0x7ac4);
+    for _ in 0..300 {
+        let pieces = random.below(20);
+        let text = random.text(pieces);
+        let mut whole = ColumnTracker::new(String::new());
+        write!(whole, "{text}")?;
+
+        let mut split = ColumnTracker::new(String::new());
+        let mut rest = text.as_str();
+        while !rest.is_empty() {
+            let mut at = random.below(rest.len()) + 1;
+            while !rest.is_char_boundary(at) {
+                at += 1;
+            }
+            write!(split, "{}", &rest[..at])?;
+            rest = &rest[at..];
+        }
+        assert_eq!(
+            (split.column(), split.positions(), split.style()),
+            (whole.column(), whole.positions(), whole.style()),
+            "{text:?}"
+        );
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn io_write_split_utf8() -> Result<()> {
+    let mut out = ColumnTracker::new(Vec::new());
+    let bytes = "x\u{4e2d}y".as_bytes();
+    for b in bytes {
+        out.write_all(&[*b])?;
+    }
+    assert_eq!((out.column(), out.positions()), (4, 3));
+    out.write_all(b"\xffz")?; // invalid byte
+    assert_eq!(out.column(), 6);
+    out.flush()?;
+    assert_eq!(out.into_inner(), b"x\xe4\xb8\xady\xffz");
+    Ok(())
+}
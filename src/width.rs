@@ -0,0 +1,152 @@
+//! Display width of print positions, in terminal columns.
+//!
+//! Terminals don't all agree on how wide some graphemes are: East Asian Ambiguous characters,
+//! emoji presentation sequences, regional indicator flags and emoji ZWJ sequences are rendered
+//! differently by different terminals (and fonts).  A [WidthProfile] names one set of rules,
+//! and [WidthOverride]s adjust a profile for particular characters.
+//! Choose them for one call with [WidthProfile::grapheme_width], or for everything done through a [Segmenter].
+//!
+//! ```rust
+//! use print_positions::width::WidthProfile;
+//!
+//! let family = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+//! assert_eq!(WidthProfile::Unicode15.grapheme_width(family), 2);
+//! assert_eq!(WidthProfile::Wcwidth.grapheme_width(family), 6);
+//! assert_eq!(WidthProfile::XtermLegacy.grapheme_width(family), 2);
+//!
+//! let flag = "\u{1f1fa}\u{1f1f8}";
+//! assert_eq!(WidthProfile::Unicode15.grapheme_width(flag), 2);
+//! assert_eq!(WidthProfile::XtermLegacy.grapheme_width(flag), 1);
+//!
+//! let ambiguous = "\u{b1}"; // plus-minus sign
+//! assert_eq!(WidthProfile::Unicode15.grapheme_width(ambiguous), 1);
+//! assert_eq!(WidthProfile::AmbiguousWide.grapheme_width(ambiguous), 2);
+//! ```
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+pub use crate::segmenter::Segmenter;
+
+#[cfg(test)]
+mod tests;
+
+/// Number of columns `s` occupies when rendered on a monospace screen: the sum of the widths of its print positions.
+///
+/// Escape sequences and control characters take no space, East Asian wide characters and emoji take 2 columns.
+/// Widths follow the default [WidthProfile], see [Segmenter::display_width] to choose another.
+///
+/// ```rust
+/// use print_positions::{display_width, print_positions};
+///
+/// let content = "\u{1b}[1m\u{4e2d}\u{6587}\u{1b}[0m text";
+/// assert_eq!(print_positions(content).count(), 7);
+/// assert_eq!(display_width(content), 9);
+/// ```
+pub fn display_width(s: &str) -> usize {
+    Segmenter::new().display_width(s)
+}
+
+/// Rules for the width of a grapheme.
+///
+/// All profiles agree that control characters take no space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum WidthProfile {
+    /// Current Unicode rules ([UAX#11](https://www.unicode.org/reports/tr11/) East Asian Width plus
+    /// [UTS#51](https://www.unicode.org/reports/tr51/) emoji sequences): emoji presentation, modifier,
+    /// flag and ZWJ sequences are 2 columns wide, East Asian Ambiguous characters are 1 column.
+    /// This is how most modern terminals render text.
+    ///
+    /// The whole grapheme is considered, not just its first character.  So `"\u{2764}\u{fe0f}"`
+    /// (heart with the emoji presentation selector VS16) is 2 columns, while `"\u{2764}\u{fe0e}"`
+    /// (text presentation selector VS15) and a bare `"\u{2764}"` are 1.  Keycaps such as
+    /// `"#\u{fe0f}\u{20e3}"` and emoji with skin tone modifiers are 2 columns.
+    #[default]
+    Unicode15,
+    /// Like [WidthProfile::Unicode15], but East Asian Ambiguous characters are 2 columns wide,
+    /// as in terminals configured for CJK locales.
+    AmbiguousWide,
+    /// The sum of the widths of the grapheme's characters, like POSIX `wcswidth()`.
+    /// Emoji sequences are as wide as all their (visible) pieces, and variation selectors are ignored.
+    Wcwidth,
+    /// The width of the grapheme's first character only, like terminals which lay out one
+    /// character per cell and draw combining characters over it.
+    /// Variation selectors are ignored and each regional indicator is 1 column.
+    XtermLegacy,
+}
+
+impl WidthProfile {
+    /// Number of columns `grapheme` occupies under this profile.
+    pub fn grapheme_width(self, grapheme: &str) -> usize {
+        let Some(first) = grapheme.chars().next() else {
+            return 0;
+        };
+        if first.is_control() {
+            return 0;
+        }
+        match self {
+            WidthProfile::Unicode15 => UnicodeWidthStr::width(grapheme),
+            WidthProfile::AmbiguousWide => UnicodeWidthStr::width_cjk(grapheme),
+            WidthProfile::Wcwidth => grapheme
+                .chars()
+                .map(|c| UnicodeWidthChar::width(c).unwrap_or(0))
+                .sum(),
+            WidthProfile::XtermLegacy => UnicodeWidthChar::width(first).unwrap_or(0),
+        }
+    }
+}
+
+/// Width to use for a range of characters, overriding the [WidthProfile].
+///
+/// An override applies to graphemes which *start* with a character in the range,
+/// unless the grapheme asks for text or emoji presentation with a variation selector
+/// (VS15 `U+FE0E` or VS16 `U+FE0F`): the profile decides the width of those.
+///
+/// ```rust
+/// use print_positions::width::{Segmenter, WidthOverride};
+///
+/// // this terminal's font draws the check marks double width
+/// const OVERRIDES: &[WidthOverride] = &[WidthOverride::new('\u{2713}', '\u{2714}', 2)];
+/// let segmenter = Segmenter::new().overrides(OVERRIDES);
+/// assert_eq!(segmenter.display_width("\u{2713} done"), 7);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WidthOverride {
+    /// First character of the range.
+    pub first: char,
+    /// Last character of the range (inclusive).
+    pub last: char,
+    /// Width of graphemes starting with a character in the range.
+    pub width: u8,
+}
+
+impl WidthOverride {
+    /// Override the width of graphemes starting with characters `first` through `last`.
+    pub const fn new(first: char, last: char, width: u8) -> Self {
+        WidthOverride { first, last, width }
+    }
+}
+
+/// Text presentation selector, VS15.
+const VS15: char = '\u{fe0e}';
+/// Emoji presentation selector, VS16.
+const VS16: char = '\u{fe0f}';
+
+/// Width of `grapheme` per `profile`, or per the first of `overrides` that applies.
+pub(crate) fn grapheme_width(
+    grapheme: &str,
+    profile: WidthProfile,
+    overrides: &[WidthOverride],
+) -> usize {
+    let mut chars = grapheme.chars();
+    if let Some(first) = chars.next() {
+        if !chars.any(|c| c == VS15 || c == VS16) {
+            if let Some(o) = overrides
+                .iter()
+                .find(|o| (o.first..=o.last).contains(&first))
+            {
+                return o.width as usize;
+            }
+        }
+    }
+    profile.grapheme_width(grapheme)
+}
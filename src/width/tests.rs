@@ -0,0 +1,128 @@
+#![cfg(test)]
+use super::*;
+
+#[allow(unused_imports)]
+use anyhow::{anyhow, Context, Result};
+
+#[test]
+fn profiles() -> Result<()> {
+    use WidthProfile::*;
+    // grapheme, then widths for Unicode15, AmbiguousWide, Wcwidth, XtermLegacy
+    let cases = [
+        ("a", [1, 1, 1, 1]),
+        ("\u{4e2d}", [2, 2, 2, 2]),
+        ("e\u{301}", [1, 1, 1, 1]),
+        ("\u{2460}", [1, 2, 1, 1]), // circled digit one, ambiguous
+        ("\u{1f1fa}\u{1f1f8}", [2, 2, 2, 1]),
+        ("\u{1f44d}\u{1f3fd}", [2, 2, 4, 2]),
+        ("\u{2764}\u{fe0f}", [2, 2, 1, 1]),
+        ("\t", [0, 0, 0, 0]),
+        ("", [0, 0, 0, 0]),
+    ];
+    for (grapheme, widths) in cases {
+        let observed =
+            [Unicode15, AmbiguousWide, Wcwidth, XtermLegacy].map(|p| p.grapheme_width(grapheme));
+        assert_eq!(widths, observed, "{grapheme:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn overrides() -> Result<()> {
+    const OVERRIDES: &[WidthOverride] = &[
+        WidthOverride::new('\u{1f1e6}', '\u{1f1ff}', 1),
+        WidthOverride::new('a', 'z', 3),
+        WidthOverride::new('a', 'a', 0), // shadowed by previous
+    ];
+    let segmenter = Segmenter::new().overrides(OVERRIDES);
+    assert_eq!(segmenter.grapheme_width("\u{1f1fa}\u{1f1f8}"), 1);
+    assert_eq!(segmenter.grapheme_width("a"), 3);
+    assert_eq!(segmenter.display_width("\x1b[1mab\u{4e2d}"), 8);
+    let widths: Vec<_> = segmenter
+        .print_position_info("A\u{1f1fa}\u{1f1f8}")
+        .map(|p| p.width())
+        .collect();
+    assert_eq!(widths, vec![1, 1]);
+    Ok(())
+}
+
+/// Parse the sample of `emoji-test.txt`: (status, emoji) per line.
+fn emoji_test_data() -> Result<Vec<(&'static str, String)>> {
+    let mut data = vec![];
+    for line in include_str!("emoji-test-sample.txt").lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let Some((code_points, status)) = line.split_once(';') else {
+            continue;
+        };
+        let emoji = code_points
+            .split_whitespace()
+            .map(|cp| {
+                u32::from_str_radix(cp, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| anyhow!("bad code point {cp:?}"))
+            })
+            .collect::<Result<String>>()?;
+        data.push((status.trim(), emoji));
+    }
+    Ok(data)
+}
+
+#[test]
+fn emoji_test_vectors() -> Result<()> {
+    let data = emoji_test_data()?;
+    assert!(data.len() > 100, "test data not found");
+    for (status, emoji) in &data {
+        assert_eq!(
+            crate::print_positions(emoji).count(),
+            1,
+            "{emoji:?} is one print position"
+        );
+        if *status == "fully-qualified" || *status == "component" {
+            for profile in [WidthProfile::Unicode15, WidthProfile::AmbiguousWide] {
+                assert_eq!(
+                    profile.grapheme_width(emoji),
+                    2,
+                    "{profile:?} width of {status} {emoji:?}"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn presentation_selectors() -> Result<()> {
+    // emoji whose default is text presentation, then the same asking for emoji or text presentation
+    let mut seen = 0;
+    for (_, emoji) in emoji_test_data()? {
+        let Some(base) = emoji.strip_suffix('\u{fe0f}') else {
+            continue;
+        };
+        if base.chars().count() != 1 {
+            continue;
+        }
+        seen += 1;
+        let text = format!("{base}\u{fe0e}");
+        let segmenter = Segmenter::new();
+        assert_eq!(segmenter.grapheme_width(base), 1, "{base:?}");
+        assert_eq!(segmenter.grapheme_width(&emoji), 2, "{emoji:?}");
+        assert_eq!(segmenter.grapheme_width(&text), 1, "{text:?}");
+    }
+    assert!(seen > 5, "too few VS16 sequences in test data");
+
+    // keycaps: only the fully-qualified form is an emoji
+    assert_eq!(display_width("#\u{fe0f}\u{20e3}"), 2);
+    assert_eq!(display_width("#\u{20e3}"), 1);
+    // a modifier sequence is one emoji, a lone modifier is a swatch
+    assert_eq!(display_width("\u{270b}\u{1f3fd}"), 2);
+    assert_eq!(display_width("\u{1f3fd}"), 2);
+
+    // presentation selectors beat overrides
+    const OVERRIDES: &[WidthOverride] = &[WidthOverride::new('\u{2764}', '\u{2764}', 3)];
+    let segmenter = Segmenter::new().overrides(OVERRIDES);
+    assert_eq!(segmenter.grapheme_width("\u{2764}"), 3);
+    assert_eq!(segmenter.grapheme_width("\u{2764}\u{fe0f}"), 2);
+    assert_eq!(segmenter.grapheme_width("\u{2764}\u{fe0e}"), 1);
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Runs the `ppos` command line tool, built with feature `cli`.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{ensure, Context, Result};
+
+// bold CJK ideograph (2 columns) between plain letters, and a family emoji (2 columns, 1 print position)
+const INPUT: &str = "a\u{1b}[1m\u{4e2d}\u{1b}[0mb\n\u{1f468}\u{200d}\u{1f467}x\n";
+
+// run ppos with `args`, feeding it `input`, and return its output
+fn ppos(args: &[&str], input: &str) -> Result<String> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ppos"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("run ppos")?;
+    child
+        .stdin
+        .take()
+        .context("stdin")?
+        .write_all(input.as_bytes())?;
+    let output = child.wait_with_output()?;
+    ensure!(
+        output.status.success(),
+        "ppos {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+fn width() -> Result<()> {
+    assert_eq!(ppos(&["width"], INPUT)?, "4\n3\n");
+    assert_eq!(ppos(&["width"], "")?, "");
+    Ok(())
+}
+
+#[test]
+fn strip() -> Result<()> {
+    assert_eq!(
+        ppos(&["strip"], INPUT)?,
+        "a\u{4e2d}b\n\u{1f468}\u{200d}\u{1f467}x\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn truncate() -> Result<()> {
+    // the ideograph fits in 3 columns, the "b" doesn't; the emoji doesn't fit in 1
+    assert_eq!(
+        ppos(&["truncate", "--width", "3"], INPUT)?,
+        "a\u{1b}[1m\u{4e2d}\u{1b}[0m\n\u{1f468}\u{200d}\u{1f467}x\n"
+    );
+    assert_eq!(ppos(&["truncate", "--width", "1"], INPUT)?, "a\n\n");
+    Ok(())
+}
+
+#[test]
+fn pad() -> Result<()> {
+    assert_eq!(
+        ppos(&["pad", "--width", "6", "--align", "right"], INPUT)?,
+        "  a\u{1b}[1m\u{4e2d}\u{1b}[0mb\n   \u{1f468}\u{200d}\u{1f467}x\n"
+    );
+    assert_eq!(
+        ppos(&["pad", "--width", "6", "--align", "center"], INPUT)?,
+        " a\u{1b}[1m\u{4e2d}\u{1b}[0mb \n \u{1f468}\u{200d}\u{1f467}x  \n"
+    );
+    assert_eq!(ppos(&["pad", "--width", "3"], "ab\n")?, "ab \n");
+    Ok(())
+}
+
+#[test]
+fn wrap() -> Result<()> {
+    assert_eq!(
+        ppos(&["wrap", "--width", "2"], INPUT)?,
+        "a\n\u{1b}[1m\u{4e2d}\u{1b}[0m\nb\n\u{1f468}\u{200d}\u{1f467}\nx\n"
+    );
+    // blank lines are kept
+    assert_eq!(ppos(&["wrap", "--width", "10"], "a\n\nb\n")?, "a\n\nb\n");
+    Ok(())
+}
+
+#[test]
+fn index() -> Result<()> {
+    assert_eq!(
+        ppos(&["index", "1"], INPUT)?,
+        "\u{1b}[1m\u{4e2d}\u{1b}[0m\nx\n"
+    );
+    assert_eq!(ppos(&["index", "3"], INPUT)?, "\n\n");
+    assert_eq!(ppos(&["index", &usize::MAX.to_string()], INPUT)?, "\n\n");
+    Ok(())
+}
+
+#[test]
+fn carried_style() -> Result<()> {
+    // red carries on to the second line, which reopens it
+    let input = "\u{1b}[31mab\ncd\u{1b}[0m\n";
+    assert_eq!(
+        ppos(&["truncate", "--width", "5"], input)?,
+        "\u{1b}[31mab\u{1b}[0m\n\u{1b}[0;31mcd\u{1b}[0m\n"
+    );
+    assert_eq!(
+        ppos(&["wrap", "--width", "1"], input)?,
+        "\u{1b}[31ma\u{1b}[0m\n\u{1b}[0;31mb\u{1b}[0m\n\u{1b}[0;31mc\u{1b}[0m\n\u{1b}[0;31md\u{1b}[0m\n"
+    );
+    assert_eq!(
+        ppos(&["index", "1"], input)?,
+        "\u{1b}[0;31mb\u{1b}[0m\n\u{1b}[0;31md\u{1b}[0m\n"
+    );
+    // and so does a hyperlink, across a blank line and a lone CR which doesn't end the line
+    let input = "\u{1b}]8;;http://x\u{7}a\n\nb\rc\u{1b}]8;;\u{7}\n";
+    assert_eq!(
+        ppos(&["truncate", "--width", "5"], input)?,
+        "\u{1b}]8;;http://x\u{7}a\u{1b}]8;;\u{1b}\\\n\n\u{1b}]8;;http://x\u{1b}\\b\rc\u{1b}]8;;\u{7}\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn column() -> Result<()> {
+    let input = "\u{1b}[34mdir\u{1b}[0m 4096\n\u{4e2d}\u{4e2d} 1\n";
+    assert_eq!(
+        ppos(&["column"], input)?,
+        "\u{1b}[34mdir\u{1b}[0m   4096\n\u{4e2d}\u{4e2d}  1\n"
+    );
+    assert_eq!(
+        ppos(&["column", "-s", ",", "-o", "|"], "a,b\nccc,d\n")?,
+        "a  |b\nccc|d\n"
+    );
+    // style carried on to the next line
+    assert_eq!(
+        ppos(&["column", "-o", "|"], "\u{1b}[31ma b\nc\u{1b}[0m d\n")?,
+        "\u{1b}[31ma\u{1b}[0m|\u{1b}[0;31mb\u{1b}[0m\n\u{1b}[0;31mc\u{1b}[0m|d\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn bad_arguments() -> Result<()> {
+    let output = Command::new(env!("CARGO_BIN_EXE_ppos"))
+        .args(["truncate"])
+        .output()?;
+    assert!(!output.status.success());
+    Ok(())
+}